@@ -4,16 +4,24 @@
 
 use crate::service::FullClient;
 
-use runtime::{AccountId, Balance, BalancesCall, SystemCall};
+use pallet_template::MAX_KITTIES_OWNED;
+use pallet_template_runtime_api::KittiesApi;
+use runtime::{AccountId, Balance, BalancesCall, SystemCall, TemplateCall};
 use sc_cli::Result;
 use sc_client_api::BlockBackend;
 use solochain_template_runtime as runtime;
+use sp_api::ProvideRuntimeApi;
 use sp_core::{Encode, Pair};
 use sp_inherents::{InherentData, InherentDataProvider};
 use sp_keyring::Sr25519Keyring;
 use sp_runtime::{OpaqueExtrinsic, SaturatedConversion};
+use substrate_frame_rpc_system::AccountNonceApi;
 
-use std::{sync::Arc, time::Duration};
+use std::{
+	collections::{hash_map::Entry, HashMap},
+	sync::Arc,
+	time::Duration,
+};
 
 /// Generates extrinsics for the `benchmark overhead` command.
 ///
@@ -92,6 +100,239 @@ impl frame_benchmarking_cli::ExtrinsicBuilder for TransferKeepAliveBuilder {
 	}
 }
 
+/// Returned when the state can't back another successful kitty call of the block being built.
+const NOT_ENOUGH_KITTIES: &str =
+	"Not enough kitties for another call. Lower `--max-ext-per-block` \
+	or benchmark on a chain spec that pre-mints kitties, see `kitties build-spec`.";
+
+/// The accounts the kitty builders sign with, all endowed in both the development and the local
+/// testnet chain specs. Spreading the calls over them lets a block hold more kitty calls than a
+/// single account could make before owning [`MAX_KITTIES_OWNED`] kitties.
+const KITTY_SIGNERS: [Sr25519Keyring; 4] = [
+	Sr25519Keyring::Bob,
+	Sr25519Keyring::Alice,
+	Sr25519Keyring::BobStash,
+	Sr25519Keyring::AliceStash,
+];
+
+/// The DNA of the kitties `who` owns at the best block.
+fn kitties_of(client: &FullClient, who: AccountId) -> Result<Vec<[u8; 32]>> {
+	let best_hash = client.chain_info().best_hash;
+	let kitties = client
+		.runtime_api()
+		.kitties_of(best_hash, who)
+		.map_err(|e| format!("Unable to query kitties: {e}"))?;
+	Ok(kitties.into_iter().map(|kitty| kitty.dna).collect())
+}
+
+/// How many more kitties `who` may receive before owning [`MAX_KITTIES_OWNED`] of them.
+fn kitty_capacity(client: &FullClient, who: AccountId) -> Result<usize> {
+	Ok((MAX_KITTIES_OWNED as usize).saturating_sub(kitties_of(client, who)?.len()))
+}
+
+/// Takes one item of every list in turn, so that the first extrinsics of a block are spread over
+/// all signers.
+fn round_robin<T>(lists: Vec<Vec<T>>) -> Vec<T> {
+	let mut lists: Vec<_> = lists.into_iter().map(Vec::into_iter).collect();
+	let mut items = Vec::new();
+	loop {
+		let before = items.len();
+		items.extend(lists.iter_mut().filter_map(Iterator::next));
+		if items.len() == before {
+			return items;
+		}
+	}
+}
+
+/// The calls of the extrinsics a kitty builder generates, each with its signer and the nonce to
+/// sign it with.
+struct KittyCalls(Vec<(Sr25519Keyring, u32, runtime::RuntimeCall)>);
+
+impl KittyCalls {
+	/// Gives the calls of every signer consecutive nonces, starting at its nonce at the best block.
+	fn new(
+		client: &FullClient,
+		calls: Vec<(Sr25519Keyring, runtime::RuntimeCall)>,
+	) -> Result<Self> {
+		let best_hash = client.chain_info().best_hash;
+		let mut nonces = HashMap::new();
+		let mut numbered = Vec::with_capacity(calls.len());
+		for (signer, call) in calls {
+			let nonce = match nonces.entry(signer) {
+				Entry::Occupied(entry) => entry.into_mut(),
+				Entry::Vacant(entry) => entry.insert(
+					client
+						.runtime_api()
+						.account_nonce(best_hash, signer.to_account_id())
+						.map_err(|e| format!("Unable to query nonce: {e}"))?,
+				),
+			};
+			numbered.push((signer, *nonce, call));
+			*nonce += 1;
+		}
+		Ok(Self(numbered))
+	}
+
+	/// Signs the call of the `index`th extrinsic of the block.
+	fn build(
+		&self,
+		client: &FullClient,
+		index: u32,
+	) -> std::result::Result<OpaqueExtrinsic, &'static str> {
+		let (signer, nonce, call) = self.0.get(index as usize).ok_or(NOT_ENOUGH_KITTIES)?;
+		Ok(create_benchmark_extrinsic(client, signer.pair(), call.clone(), *nonce).into())
+	}
+}
+
+/// Generates `TemplateModule::create_kitty` extrinsics for the benchmarks.
+///
+/// The mints are spread over [`KITTY_SIGNERS`], each minting until it owns
+/// [`MAX_KITTIES_OWNED`] kitties.
+///
+/// Note: Should only be used for benchmarking.
+pub struct CreateKittyBuilder {
+	client: Arc<FullClient>,
+	calls: KittyCalls,
+}
+
+impl CreateKittyBuilder {
+	/// Creates a new [`Self`] from the given client.
+	pub fn new(client: Arc<FullClient>) -> Result<Self> {
+		let mut mints = Vec::new();
+		for signer in KITTY_SIGNERS {
+			let capacity = kitty_capacity(&client, signer.to_account_id())?;
+			mints.push(vec![
+				(signer, runtime::RuntimeCall::from(TemplateCall::create_kitty {}));
+				capacity
+			]);
+		}
+		let calls = KittyCalls::new(&client, round_robin(mints))?;
+		Ok(Self { client, calls })
+	}
+}
+
+impl frame_benchmarking_cli::ExtrinsicBuilder for CreateKittyBuilder {
+	fn pallet(&self) -> &str {
+		"template_module"
+	}
+
+	fn extrinsic(&self) -> &str {
+		"create_kitty"
+	}
+
+	fn build(&self, nonce: u32) -> std::result::Result<OpaqueExtrinsic, &'static str> {
+		self.calls.build(self.client.as_ref(), nonce)
+	}
+}
+
+/// Generates `TemplateModule::transfer` extrinsics for the benchmarks.
+///
+/// Every one of [`KITTY_SIGNERS`] transfers its kitties to the next one, as many as the next one
+/// has room for.
+///
+/// Note: Should only be used for benchmarking.
+pub struct KittyTransferBuilder {
+	client: Arc<FullClient>,
+	calls: KittyCalls,
+}
+
+impl KittyTransferBuilder {
+	/// Creates a new [`Self`] from the given client.
+	pub fn new(client: Arc<FullClient>) -> Result<Self> {
+		let mut transfers = Vec::new();
+		for (index, signer) in KITTY_SIGNERS.into_iter().enumerate() {
+			let to = KITTY_SIGNERS[(index + 1) % KITTY_SIGNERS.len()].to_account_id();
+			let mut kitties = kitties_of(&client, signer.to_account_id())?;
+			// Only the sender's own kitties leave it, so the recipient never gets more than this.
+			kitties.truncate(kitty_capacity(&client, to.clone())?);
+			let transfer = |kitty_id| {
+				runtime::RuntimeCall::from(TemplateCall::transfer { to: to.clone(), kitty_id })
+			};
+			transfers.push(kitties.into_iter().map(|kitty| (signer, transfer(kitty))).collect());
+		}
+		let calls = KittyCalls::new(&client, round_robin(transfers))?;
+		Ok(Self { client, calls })
+	}
+}
+
+impl frame_benchmarking_cli::ExtrinsicBuilder for KittyTransferBuilder {
+	fn pallet(&self) -> &str {
+		"template_module"
+	}
+
+	fn extrinsic(&self) -> &str {
+		"transfer"
+	}
+
+	fn build(&self, nonce: u32) -> std::result::Result<OpaqueExtrinsic, &'static str> {
+		self.calls.build(self.client.as_ref(), nonce)
+	}
+}
+
+/// Generates `TemplateModule::buy_kitty` extrinsics for the benchmarks.
+///
+/// Every extrinsic buys another listed kitty at its asking price. The purchases are spread over
+/// [`KITTY_SIGNERS`], none of which buys its own kitties or more than it has room for.
+///
+/// Note: Should only be used for benchmarking.
+pub struct BuyKittyBuilder {
+	client: Arc<FullClient>,
+	calls: KittyCalls,
+}
+
+impl BuyKittyBuilder {
+	/// Creates a new [`Self`] from the given client.
+	pub fn new(client: Arc<FullClient>) -> Result<Self> {
+		let capacities = KITTY_SIGNERS
+			.into_iter()
+			.map(|signer| kitty_capacity(&client, signer.to_account_id()))
+			.collect::<Result<Vec<_>>>()?;
+		let has_room =
+			|purchases: &[Vec<_>], buyer: usize| purchases[buyer].len() < capacities[buyer];
+		let best_hash = client.chain_info().best_hash;
+
+		let mut purchases = vec![Vec::new(); KITTY_SIGNERS.len()];
+		let mut start = None;
+		while (0..KITTY_SIGNERS.len()).any(|buyer| has_room(&purchases, buyer)) {
+			let page = client
+				.runtime_api()
+				.listed_kitties(best_hash, start, MAX_KITTIES_OWNED)
+				.map_err(|e| format!("Unable to query kitties: {e}"))?;
+			let Some(last) = page.last() else { break };
+			start = Some(last.dna);
+			for kitty in page {
+				let Some(max_price) = kitty.price else { continue };
+				// Of the signers that may buy the kitty, the one that bought the fewest so far.
+				let Some(buyer) = (0..KITTY_SIGNERS.len())
+					.filter(|&buyer| KITTY_SIGNERS[buyer].to_account_id() != kitty.owner)
+					.filter(|&buyer| has_room(&purchases, buyer))
+					.min_by_key(|&buyer| purchases[buyer].len())
+				else {
+					continue;
+				};
+				let call = TemplateCall::buy_kitty { kitty_id: kitty.dna, max_price };
+				purchases[buyer].push((KITTY_SIGNERS[buyer], runtime::RuntimeCall::from(call)));
+			}
+		}
+		let calls = KittyCalls::new(&client, round_robin(purchases))?;
+		Ok(Self { client, calls })
+	}
+}
+
+impl frame_benchmarking_cli::ExtrinsicBuilder for BuyKittyBuilder {
+	fn pallet(&self) -> &str {
+		"template_module"
+	}
+
+	fn extrinsic(&self) -> &str {
+		"buy_kitty"
+	}
+
+	fn build(&self, nonce: u32) -> std::result::Result<OpaqueExtrinsic, &'static str> {
+		self.calls.build(self.client.as_ref(), nonce)
+	}
+}
+
 /// Create a transaction using the given `call`.
 ///
 /// Note: Should only be used for benchmarking.
@@ -105,6 +346,21 @@ pub fn create_benchmark_extrinsic(
 	let best_hash = client.chain_info().best_hash;
 	let best_block = client.chain_info().best_number;
 
+	sign_extrinsic(sender, call, nonce, genesis_hash, best_hash, best_block)
+}
+
+/// Create a transaction using the given `call`, valid from block `best_block` with hash
+/// `best_hash` on.
+///
+/// Note: Should only be used for benchmarking and load testing.
+pub fn sign_extrinsic(
+	sender: sp_core::sr25519::Pair,
+	call: runtime::RuntimeCall,
+	nonce: u32,
+	genesis_hash: runtime::Hash,
+	best_hash: runtime::Hash,
+	best_block: runtime::BlockNumber,
+) -> runtime::UncheckedExtrinsic {
 	let period = runtime::configs::BlockHashCount::get()
 		.checked_next_power_of_two()
 		.map(|c| c / 2)
@@ -118,9 +374,13 @@ pub fn create_benchmark_extrinsic(
 			period,
 			best_block.saturated_into(),
 		)),
+		pallet_template::CheckFreeMint::<runtime::Runtime>::new(),
 		frame_system::CheckNonce::<runtime::Runtime>::from(nonce),
 		frame_system::CheckWeight::<runtime::Runtime>::new(),
-		pallet_transaction_payment::ChargeTransactionPayment::<runtime::Runtime>::from(0),
+		pallet_template::CheckKittyPurchase::<runtime::Runtime>::new(),
+		pallet_skip_feeless_payment::SkipCheckIfFeeless::from(
+			pallet_transaction_payment::ChargeTransactionPayment::<runtime::Runtime>::from(0),
+		),
 		frame_metadata_hash_extension::CheckMetadataHash::<runtime::Runtime>::new(false),
 	);
 
@@ -136,6 +396,8 @@ pub fn create_benchmark_extrinsic(
 			(),
 			(),
 			(),
+			(),
+			(),
 			None,
 		),
 	);
@@ -1,9 +1,15 @@
 use sc_service::ChainType;
-use solochain_template_runtime::{AccountId, Signature, WASM_BINARY};
+use solochain_template_runtime::{
+	configs::TreasuryAccount, AccountId, Balance, Signature, EXISTENTIAL_DEPOSIT, MILLI_UNIT,
+	WASM_BINARY,
+};
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_consensus_grandpa::AuthorityId as GrandpaId;
 use sp_core::{sr25519, Pair, Public};
-use sp_runtime::traits::{IdentifyAccount, Verify};
+use sp_runtime::{
+	traits::{IdentifyAccount, Verify},
+	Permill,
+};
 
 // The URL for the telemetry server.
 // const STAGING_TELEMETRY_URL: &str = "wss://telemetry.polkadot.io/submit/";
@@ -53,6 +59,13 @@ pub fn development_config() -> Result<ChainSpec, String> {
 			get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
 			get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
 		],
+		// Council members
+		vec![
+			get_account_id_from_seed::<sr25519::Public>("Alice"),
+			get_account_id_from_seed::<sr25519::Public>("Bob"),
+		],
+		// Genesis kitties
+		vec![],
 		true,
 	))
 	.build())
@@ -86,6 +99,44 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 			get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
 			get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
 		],
+		// Council members
+		vec![
+			get_account_id_from_seed::<sr25519::Public>("Alice"),
+			get_account_id_from_seed::<sr25519::Public>("Bob"),
+			get_account_id_from_seed::<sr25519::Public>("Charlie"),
+		],
+		// Genesis kitties
+		vec![],
+		true,
+	))
+	.build())
+}
+
+/// A development chain whose genesis pre-mints `kitties`, as `(owner, dna, price)`.
+///
+/// Alice is the only authority, the sudo key and the only council member. She is endowed together
+/// with `endowed_accounts`.
+pub fn roster_config(
+	kitties: Vec<(AccountId, [u8; 32], Option<Balance>)>,
+	endowed_accounts: Vec<AccountId>,
+) -> Result<ChainSpec, String> {
+	let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+	let mut endowed = vec![alice.clone()];
+	endowed.extend(endowed_accounts.into_iter().filter(|account| *account != alice));
+
+	Ok(ChainSpec::builder(
+		WASM_BINARY.ok_or_else(|| "Development wasm not available".to_string())?,
+		None,
+	)
+	.with_name("Kitties Roster")
+	.with_id("kitties_roster")
+	.with_chain_type(ChainType::Development)
+	.with_genesis_config_patch(testnet_genesis(
+		vec![authority_keys_from_seed("Alice")],
+		alice.clone(),
+		endowed,
+		vec![alice],
+		kitties,
 		true,
 	))
 	.build())
@@ -96,12 +147,23 @@ fn testnet_genesis(
 	initial_authorities: Vec<(AuraId, GrandpaId)>,
 	root_key: AccountId,
 	endowed_accounts: Vec<AccountId>,
+	council_members: Vec<AccountId>,
+	kitties: Vec<(AccountId, [u8; 32], Option<Balance>)>,
 	_enable_println: bool,
 ) -> serde_json::Value {
+	// Configure endowed accounts with initial balance of 1 << 60.
+	let mut balances: Vec<(AccountId, Balance)> =
+		endowed_accounts.iter().cloned().map(|k| (k, 1 << 60)).collect();
+	// The treasury collects the fees. It has to exist from the start, as fees below the
+	// existential deposit can't create it.
+	let treasury = TreasuryAccount::get();
+	if !endowed_accounts.contains(&treasury) {
+		balances.push((treasury, EXISTENTIAL_DEPOSIT));
+	}
+
 	serde_json::json!({
 		"balances": {
-			// Configure endowed accounts with initial balance of 1 << 60.
-			"balances": endowed_accounts.iter().cloned().map(|k| (k, 1u64 << 60)).collect::<Vec<_>>(),
+			"balances": balances,
 		},
 		"aura": {
 			"authorities": initial_authorities.iter().map(|x| (x.0.clone())).collect::<Vec<_>>(),
@@ -113,5 +175,15 @@ fn testnet_genesis(
 			// Assign network admin rights.
 			"key": Some(root_key),
 		},
+		"council": {
+			// Can use the marketplace's privileged calls, see `ForceOrigin` and `PauseOrigin`.
+			"members": council_members,
+		},
+		"templateModule": {
+			"kitties": kitties,
+			// 0.01 UNIT for every paid mint and 2.5% of every sale go to the treasury.
+			"mintFee": 10 * MILLI_UNIT,
+			"marketplaceFee": Permill::from_parts(25_000),
+		},
 	})
 }
@@ -1,5 +1,36 @@
 use sc_cli::RunCmd;
 
+pub mod kitties;
+pub mod load;
+
+/// How blocks are sealed when the node runs without Aura and GRANDPA.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sealing {
+	/// Seal a block as soon as a transaction enters the pool.
+	Instant,
+	/// Seal blocks only when asked to through `engine_createBlock`.
+	Manual,
+	/// Seal a block every given number of milliseconds.
+	Interval(u64),
+}
+
+impl std::str::FromStr for Sealing {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"instant" => Ok(Sealing::Instant),
+			"manual" => Ok(Sealing::Manual),
+			millis => match millis.parse() {
+				Ok(0) | Err(_) => Err(format!(
+					"Expected `instant`, `manual` or a positive number of milliseconds, got `{s}`"
+				)),
+				Ok(millis) => Ok(Sealing::Interval(millis)),
+			},
+		}
+	}
+}
+
 #[derive(Debug, clap::Parser)]
 pub struct Cli {
 	#[command(subcommand)]
@@ -7,6 +38,17 @@ pub struct Cli {
 
 	#[clap(flatten)]
 	pub run: RunCmd,
+
+	/// Seal blocks with manual seal instead of Aura and GRANDPA, for local development.
+	///
+	/// `instant` seals a block for every transaction, `manual` only when `engine_createBlock` is
+	/// called and a number seals a block every that many milliseconds. Blocks can be finalized
+	/// with `engine_finalizeBlock`; instant and interval blocks are finalized right away.
+	///
+	/// Also give it to the sub-commands that import or check blocks of such a chain, as its
+	/// blocks carry no Aura seal.
+	#[arg(long, global = true, value_name = "instant|manual|MILLIS")]
+	pub sealing: Option<Sealing>,
 }
 
 #[derive(Debug, clap::Subcommand)]
@@ -43,4 +85,44 @@ pub enum Subcommand {
 
 	/// Db meta columns information.
 	ChainInfo(sc_cli::ChainInfoCmd),
+
+	/// Sub-commands for working with the kitties of a local chain.
+	#[command(subcommand)]
+	Kitties(kitties::KittiesSubcommand),
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use clap::Parser;
+
+	#[test]
+	fn sealing_from_str() {
+		assert_eq!("instant".parse(), Ok(Sealing::Instant));
+		assert_eq!("manual".parse(), Ok(Sealing::Manual));
+		assert_eq!("6000".parse(), Ok(Sealing::Interval(6000)));
+
+		for invalid in ["0", "-1", "Instant", "", "1.5", "six"] {
+			assert!(invalid.parse::<Sealing>().is_err(), "`{invalid}` should be rejected");
+		}
+	}
+
+	#[test]
+	fn sealing_flag_is_parsed() {
+		let cli = Cli::try_parse_from(["solochain-template-node", "--sealing", "250"]).unwrap();
+		assert_eq!(cli.sealing, Some(Sealing::Interval(250)));
+		assert!(Cli::try_parse_from(["solochain-template-node", "--sealing", "0"]).is_err());
+
+		// Sub-commands that import blocks have to know how they were sealed.
+		let cli = Cli::try_parse_from([
+			"solochain-template-node",
+			"import-blocks",
+			"--sealing",
+			"instant",
+			"blocks.bin",
+		])
+		.unwrap();
+		assert!(matches!(cli.subcommand, Some(Subcommand::ImportBlocks(_))));
+		assert_eq!(cli.sealing, Some(Sealing::Instant));
+	}
 }
@@ -0,0 +1,443 @@
+//! The `kitties` sub-commands.
+
+use std::{
+	collections::{HashMap, HashSet},
+	fs::File,
+	io::Write,
+	path::{Path, PathBuf},
+	sync::Arc,
+};
+
+use codec::Decode;
+use frame_support::traits::PalletInfoAccess;
+use sc_cli::{BlockNumberOrHash, CliConfiguration, DatabaseParams, PruningParams, SharedParams};
+use sc_client_api::{Backend, StorageProvider};
+use serde::{Deserialize, Serialize};
+use solochain_template_runtime::{opaque::Block, AccountId, Balance, Runtime, TemplateModule};
+use sp_blockchain::HeaderBackend;
+use sp_core::{crypto::Ss58Codec, storage::StorageKey};
+
+/// Sub-commands for working with the kitties of a local chain.
+#[derive(Debug, clap::Subcommand)]
+pub enum KittiesSubcommand {
+	/// Export every kitty at a given block as JSON or CSV.
+	Export(ExportKittiesCmd),
+
+	/// Build a chain spec whose genesis pre-mints the kitties of a roster file.
+	BuildSpec(RosterSpecCmd),
+
+	/// Send a mix of kitty transactions to a local node and report TPS and inclusion latency.
+	Load(super::load::LoadKittiesCmd),
+}
+
+/// The file formats `kitties export` writes and `kitties build-spec` reads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum FileFormat {
+	Json,
+	Csv,
+}
+
+/// The `kitties export` command.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct ExportKittiesCmd {
+	/// Block hash or number to export the kitties at. Defaults to the best block.
+	#[arg(value_name = "HASH or NUMBER")]
+	pub input: Option<BlockNumberOrHash>,
+
+	/// Format of the export.
+	#[arg(long, value_enum, default_value_t = FileFormat::Json)]
+	pub format: FileFormat,
+
+	/// File to write the export to. Defaults to stdout.
+	#[arg(long, short, value_name = "PATH")]
+	pub output: Option<PathBuf>,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+/// A kitty as written by `kitties export` and read by `kitties build-spec`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedKitty {
+	/// The DNA of the kitty, hex encoded.
+	pub dna: String,
+	/// The SS58 address of the owner.
+	pub owner: String,
+	/// The asking price, if the kitty is listed for sale. Written as a decimal string, as JSON
+	/// numbers can't hold every balance.
+	#[serde(default, with = "decimal")]
+	pub price: Option<Balance>,
+	/// Position of the kitty in its owner's list of kitties. `None` if the kitty is missing from
+	/// that list, which means the pallet's storage is corrupted.
+	pub owner_index: Option<u32>,
+}
+
+/// (De)serializes an optional balance as a decimal string.
+mod decimal {
+	use serde::{de::Error, Deserialize, Deserializer, Serializer};
+	use solochain_template_runtime::Balance;
+
+	pub fn serialize<S: Serializer>(
+		value: &Option<Balance>,
+		serializer: S,
+	) -> Result<S::Ok, S::Error> {
+		match value {
+			Some(value) => serializer.collect_str(value),
+			None => serializer.serialize_none(),
+		}
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(
+		deserializer: D,
+	) -> Result<Option<Balance>, D::Error> {
+		Option::<String>::deserialize(deserializer)?
+			.map(|value| value.parse().map_err(D::Error::custom))
+			.transpose()
+	}
+}
+
+/// The key prefix of the given storage item of the kitties pallet.
+fn storage_prefix(item: &str) -> StorageKey {
+	let pallet = <TemplateModule as PalletInfoAccess>::name();
+	StorageKey(frame_support::storage::storage_prefix(pallet.as_bytes(), item.as_bytes()).to_vec())
+}
+
+impl ExportKittiesCmd {
+	/// Reads the kitties from `client` and writes them out.
+	pub fn run<C, BE>(&self, client: Arc<C>) -> sc_cli::Result<()>
+	where
+		C: StorageProvider<Block, BE> + HeaderBackend<Block>,
+		BE: Backend<Block>,
+	{
+		let hash = match &self.input {
+			Some(input) => {
+				let id = input.parse::<Block>()?;
+				client.block_hash_from_id(&id)?.ok_or_else(|| format!("Unknown block {id:?}"))?
+			},
+			None => client.info().best_hash,
+		};
+
+		let kitties = Self::read_kitties(&*client, hash)?;
+		let mut out = output(self.output.as_deref())?;
+		match self.format {
+			FileFormat::Json => {
+				serde_json::to_writer_pretty(&mut out, &kitties)
+					.map_err(|e| format!("Failed to write JSON: {e}"))?;
+				writeln!(out)?;
+			},
+			FileFormat::Csv => write_csv(&mut out, &kitties)?,
+		}
+		Ok(())
+	}
+
+	/// Decodes all kitties, and the index of each of them in its owner's list, from the storage
+	/// of block `hash`.
+	fn read_kitties<C, BE>(
+		client: &C,
+		hash: <Block as sp_runtime::traits::Block>::Hash,
+	) -> sc_cli::Result<Vec<ExportedKitty>>
+	where
+		C: StorageProvider<Block, BE>,
+		BE: Backend<Block>,
+	{
+		let mut owner_index = HashMap::new();
+		for (_, value) in client.storage_pairs(hash, Some(&storage_prefix("KittiesOwned")), None)? {
+			let owned = decode::<Vec<[u8; 32]>>(&value.0, "KittiesOwned")?;
+			for (index, dna) in owned.into_iter().enumerate() {
+				owner_index.insert(dna, index as u32);
+			}
+		}
+
+		let mut kitties = Vec::new();
+		for (_, value) in client.storage_pairs(hash, Some(&storage_prefix("Kitties")), None)? {
+			let kitty = decode::<pallet_template::Kitty<Runtime>>(&value.0, "Kitties")?;
+			kitties.push(ExportedKitty {
+				dna: format!("0x{}", hex::encode(kitty.dna)),
+				owner: kitty.owner.to_ss58check(),
+				price: kitty.price,
+				owner_index: owner_index.get(&kitty.dna).copied(),
+			});
+		}
+		Ok(kitties)
+	}
+}
+
+/// Writes `kitties` as CSV, in the format [`parse_csv`] reads.
+fn write_csv(out: &mut dyn Write, kitties: &[ExportedKitty]) -> std::io::Result<()> {
+	writeln!(out, "dna,owner,price,owner_index")?;
+	for kitty in kitties {
+		let price = kitty.price.map(|price| price.to_string()).unwrap_or_default();
+		let index = kitty.owner_index.map(|index| index.to_string()).unwrap_or_default();
+		writeln!(out, "{},{},{},{}", kitty.dna, kitty.owner, price, index)?;
+	}
+	Ok(())
+}
+
+/// Opens `path` for writing, or stdout if there is no path.
+fn output(path: Option<&Path>) -> std::io::Result<Box<dyn Write>> {
+	Ok(match path {
+		Some(path) => Box::new(File::create(path)?),
+		None => Box::new(std::io::stdout()),
+	})
+}
+
+fn decode<T: Decode>(mut value: &[u8], item: &str) -> sc_cli::Result<T> {
+	T::decode(&mut value).map_err(|e| format!("Failed to decode {item} entry: {e}").into())
+}
+
+impl CliConfiguration for ExportKittiesCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
+
+/// The `kitties build-spec` command.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct RosterSpecCmd {
+	/// JSON or CSV file listing the kitties to pre-mint.
+	///
+	/// Both formats written by `kitties export` are accepted. A JSON roster may also be an object
+	/// with `kitties` and `endowed` lists, the latter holding SS58 addresses to endow.
+	#[arg(value_name = "ROSTER")]
+	pub roster: PathBuf,
+
+	/// Format of the roster. Guessed from the file extension by default.
+	#[arg(long, value_enum)]
+	pub format: Option<FileFormat>,
+
+	/// Endow this account as well. May be given multiple times.
+	#[arg(long = "endow", value_name = "SS58")]
+	pub endowed: Vec<AccountId>,
+
+	/// Write the chain spec in raw storage format.
+	#[arg(long)]
+	pub raw: bool,
+
+	/// File to write the chain spec to. Defaults to stdout.
+	#[arg(long, short, value_name = "PATH")]
+	pub output: Option<PathBuf>,
+}
+
+/// The JSON roster formats.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonRoster {
+	/// A plain list of kitties, as written by `kitties export`.
+	Kitties(Vec<ExportedKitty>),
+	/// Kitties together with accounts to endow.
+	Full {
+		#[serde(default)]
+		kitties: Vec<ExportedKitty>,
+		#[serde(default)]
+		endowed: Vec<AccountId>,
+	},
+}
+
+impl RosterSpecCmd {
+	/// Reads the roster and writes the chain spec.
+	pub fn run(&self) -> sc_cli::Result<()> {
+		let format = self.format.unwrap_or_else(|| {
+			match self.roster.extension().and_then(|extension| extension.to_str()) {
+				Some(extension) if extension.eq_ignore_ascii_case("csv") => FileFormat::Csv,
+				_ => FileFormat::Json,
+			}
+		});
+		let content = std::fs::read_to_string(&self.roster)?;
+		let (mut roster, mut endowed) = match format {
+			FileFormat::Json => match serde_json::from_str::<JsonRoster>(&content)
+				.map_err(|e| format!("Invalid JSON roster: {e}"))?
+			{
+				JsonRoster::Kitties(kitties) => (kitties, Vec::new()),
+				JsonRoster::Full { kitties, endowed } => (kitties, endowed),
+			},
+			FileFormat::Csv => (parse_csv(&content)?, Vec::new()),
+		};
+
+		let kitties = genesis_kitties(&mut roster)?;
+		endowed.extend(kitties.iter().map(|(owner, _, _)| owner.clone()));
+		endowed.extend(self.endowed.iter().cloned());
+		// Genesis rejects duplicate balances.
+		let mut seen = HashSet::new();
+		endowed.retain(|account| seen.insert(account.clone()));
+
+		let spec = crate::chain_spec::roster_config(kitties, endowed)?;
+		let mut out = output(self.output.as_deref())?;
+		writeln!(out, "{}", spec.as_json(self.raw)?)?;
+		Ok(())
+	}
+}
+
+/// Turns the roster into the kitties to mint at genesis.
+///
+/// Genesis panics on kitties it can't mint, so this rejects duplicate DNA and owners of more than
+/// [`pallet_template::MAX_KITTIES_OWNED`] kitties up front.
+fn genesis_kitties(
+	roster: &mut [ExportedKitty],
+) -> sc_cli::Result<Vec<pallet_template::GenesisKitty<Runtime>>> {
+	// Keep the order of every owner's kitties from the chain the roster was exported from.
+	roster.sort_by_key(|kitty| kitty.owner_index.unwrap_or(u32::MAX));
+
+	let mut kitties = Vec::with_capacity(roster.len());
+	let mut seen = HashSet::new();
+	let mut owned = HashMap::<AccountId, u32>::new();
+	for kitty in roster.iter() {
+		let owner = AccountId::from_ss58check(&kitty.owner)
+			.map_err(|e| format!("Invalid owner {}: {e:?}", kitty.owner))?;
+		let dna = hex::decode(kitty.dna.trim_start_matches("0x"))
+			.ok()
+			.and_then(|dna| <[u8; 32]>::try_from(dna).ok())
+			.ok_or_else(|| format!("Invalid DNA {}", kitty.dna))?;
+		if !seen.insert(dna) {
+			return Err(format!("Duplicate DNA {}", kitty.dna).into());
+		}
+		let count = owned.entry(owner.clone()).or_default();
+		*count += 1;
+		if *count > pallet_template::MAX_KITTIES_OWNED {
+			return Err(format!(
+				"{} owns more than {} kitties",
+				kitty.owner,
+				pallet_template::MAX_KITTIES_OWNED
+			)
+			.into());
+		}
+		kitties.push((owner, dna, kitty.price));
+	}
+	Ok(kitties)
+}
+
+/// Parses a CSV roster with `dna`, `owner` and optionally `price` and `owner_index` columns.
+fn parse_csv(content: &str) -> sc_cli::Result<Vec<ExportedKitty>> {
+	let mut lines = content.lines().filter(|line| !line.trim().is_empty());
+	let header: Vec<_> = lines.next().unwrap_or_default().split(',').map(str::trim).collect();
+	let column = |name: &str| header.iter().position(|column| *column == name);
+	let (dna, owner) = match (column("dna"), column("owner")) {
+		(Some(dna), Some(owner)) => (dna, owner),
+		_ => return Err("The CSV roster needs `dna` and `owner` columns".into()),
+	};
+	let (price, owner_index) = (column("price"), column("owner_index"));
+
+	lines
+		.enumerate()
+		.map(|(row, line)| -> sc_cli::Result<ExportedKitty> {
+			let row = row + 1;
+			let fields: Vec<_> = line.split(',').map(str::trim).collect();
+			let field = |index: Option<usize>| {
+				index.and_then(|index| fields.get(index).copied()).filter(|field| !field.is_empty())
+			};
+			let required = |index: usize, name: &str| {
+				field(Some(index))
+					.map(str::to_string)
+					.ok_or_else(|| format!("Missing {name} on row {row}"))
+			};
+			Ok(ExportedKitty {
+				dna: required(dna, "dna")?,
+				owner: required(owner, "owner")?,
+				price: parse_number(field(price), "price", row)?,
+				owner_index: parse_number(field(owner_index), "owner_index", row)?,
+			})
+		})
+		.collect()
+}
+
+fn parse_number<T: std::str::FromStr>(
+	value: Option<&str>,
+	name: &str,
+	row: usize,
+) -> Result<Option<T>, String>
+where
+	T::Err: std::fmt::Display,
+{
+	value
+		.map(|value| value.parse())
+		.transpose()
+		.map_err(|e| format!("Invalid {name} on row {row}: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_keyring::Sr25519Keyring;
+
+	fn kitty(dna: u8, owner: Sr25519Keyring, price: Option<Balance>) -> ExportedKitty {
+		ExportedKitty {
+			dna: format!("0x{}", hex::encode([dna; 32])),
+			owner: owner.to_account_id().to_ss58check(),
+			price,
+			owner_index: Some(0),
+		}
+	}
+
+	#[test]
+	fn parse_csv_reads_optional_columns() {
+		let alice = Sr25519Keyring::Alice.to_account_id().to_ss58check();
+		let content = format!("owner, dna\n\n{alice}, 0x01\n{alice},0x02\n");
+		let kitties = parse_csv(&content).unwrap();
+		assert_eq!(kitties.len(), 2);
+		assert_eq!(kitties[1].dna, "0x02");
+		assert_eq!(kitties[1].owner, alice);
+		assert_eq!((kitties[1].price, kitties[1].owner_index), (None, None));
+	}
+
+	#[test]
+	fn parse_csv_rejects_bad_rows() {
+		assert!(parse_csv("dna,price\n0x01,5\n").is_err());
+		assert!(parse_csv("dna,owner\n0x01,\n").is_err());
+		assert!(parse_csv("dna,owner,price\n0x01,alice,lots\n").is_err());
+	}
+
+	#[test]
+	fn export_round_trips() {
+		let kitties = vec![
+			kitty(1, Sr25519Keyring::Alice, Some(10)),
+			ExportedKitty { owner_index: None, ..kitty(2, Sr25519Keyring::Bob, None) },
+			kitty(3, Sr25519Keyring::Charlie, Some(Balance::MAX)),
+		];
+
+		let mut csv = Vec::new();
+		write_csv(&mut csv, &kitties).unwrap();
+		assert_eq!(parse_csv(&String::from_utf8(csv).unwrap()).unwrap(), kitties);
+
+		let json = serde_json::to_string(&kitties).unwrap();
+		assert!(json.contains(r#""price":"340282366920938463463374607431768211455""#));
+		match serde_json::from_str::<JsonRoster>(&json).unwrap() {
+			JsonRoster::Kitties(parsed) => assert_eq!(parsed, kitties),
+			JsonRoster::Full { .. } => panic!("an export is a plain list of kitties"),
+		}
+	}
+
+	#[test]
+	fn genesis_kitties_rejects_duplicate_dna() {
+		let mut roster =
+			vec![kitty(1, Sr25519Keyring::Alice, None), kitty(1, Sr25519Keyring::Bob, None)];
+		assert!(genesis_kitties(&mut roster).is_err());
+	}
+
+	#[test]
+	fn genesis_kitties_rejects_too_many_per_owner() {
+		let max = pallet_template::MAX_KITTIES_OWNED as u8;
+		let mut roster: Vec<_> =
+			(0..max).map(|dna| kitty(dna, Sr25519Keyring::Alice, None)).collect();
+		assert_eq!(genesis_kitties(&mut roster).unwrap().len(), max as usize);
+
+		roster.push(kitty(max, Sr25519Keyring::Alice, None));
+		assert!(genesis_kitties(&mut roster).is_err());
+	}
+}
@@ -0,0 +1,414 @@
+//! The `kitties load` command, which generates kitty traffic against a local node.
+
+use std::{
+	cmp::Reverse,
+	collections::HashMap,
+	time::{Duration, Instant},
+};
+
+use codec::Encode;
+use futures::future;
+use jsonrpsee::{
+	core::client::{ClientT, SubscriptionClientT},
+	rpc_params,
+	ws_client::{WsClient, WsClientBuilder},
+};
+use pallet_template::MAX_KITTIES_OWNED;
+use sc_transaction_pool_api::TransactionStatus;
+use solochain_template_runtime::{
+	AccountId, Balance, BlockNumber, Hash, Header, RuntimeCall, TemplateCall, UNIT,
+};
+use sp_core::{hashing::blake2_256, Bytes};
+use sp_keyring::Sr25519Keyring;
+use sp_runtime::traits::Header as HeaderT;
+
+use crate::{benchmarking::sign_extrinsic, rpc::kitties::KittiesApiClient};
+
+/// The dev accounts that send the load, in order.
+const DEV_ACCOUNTS: [Sr25519Keyring; 6] = [
+	Sr25519Keyring::Alice,
+	Sr25519Keyring::Bob,
+	Sr25519Keyring::Charlie,
+	Sr25519Keyring::Dave,
+	Sr25519Keyring::Eve,
+	Sr25519Keyring::Ferdie,
+];
+
+/// The most listed kitties fetched per `kitties_listed` call.
+const LISTINGS_PAGE_SIZE: u32 = 500;
+
+/// The `kitties load` command.
+///
+/// Sends a mix of mints, listings, transfers and buys, signed with the dev keys, to a node over
+/// RPC and reports the throughput and inclusion latency. The load is sent in rounds: every round
+/// plans its transactions from the chain state left by the previous one, submits them all at
+/// once and waits until each of them is included in a block. The same seed on the same chain
+/// state yields the same transactions.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct LoadKittiesCmd {
+	/// WebSocket RPC endpoint of the node.
+	#[arg(long, default_value = "ws://127.0.0.1:9944")]
+	pub url: String,
+
+	/// Number of dev accounts sending transactions, starting with Alice.
+	#[arg(long, default_value_t = 6, value_parser = clap::value_parser!(u8).range(1..=6))]
+	pub accounts: u8,
+
+	/// Number of rounds.
+	#[arg(long, default_value_t = 10)]
+	pub rounds: u32,
+
+	/// Number of transactions every account sends per round.
+	#[arg(long, default_value_t = 10)]
+	pub per_account: u32,
+
+	/// Relative share of mints in the mix.
+	#[arg(long, default_value_t = 4)]
+	pub mints: u32,
+
+	/// Relative share of listings in the mix.
+	#[arg(long, default_value_t = 2)]
+	pub listings: u32,
+
+	/// Relative share of transfers in the mix.
+	#[arg(long, default_value_t = 2)]
+	pub transfers: u32,
+
+	/// Relative share of buys in the mix.
+	#[arg(long, default_value_t = 2)]
+	pub buys: u32,
+
+	/// Asking price of listed kitties, in native units.
+	#[arg(long, default_value_t = 1)]
+	pub price: u32,
+
+	/// Seed for choosing the transactions.
+	#[arg(long, default_value_t = 0)]
+	pub seed: u64,
+
+	/// Seconds to wait for a transaction to be included before counting it as failed.
+	#[arg(long, default_value_t = 60)]
+	pub timeout: u64,
+}
+
+/// A kind of transaction in the mix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operation {
+	Mint,
+	List,
+	Transfer,
+	Buy,
+}
+
+/// What a round needs to know about one of the sending accounts.
+struct AccountState {
+	account: AccountId,
+	nonce: u32,
+	/// Number of kitties the account owns or receives in the round.
+	owned: usize,
+	/// Kitties of the account that are not listed and not yet used in the round.
+	unlisted: Vec<[u8; 32]>,
+}
+
+impl AccountState {
+	fn has_room(&self) -> bool {
+		self.owned < MAX_KITTIES_OWNED as usize
+	}
+}
+
+/// The chain state a round plans its transactions from.
+struct Snapshot {
+	genesis_hash: Hash,
+	best_hash: Hash,
+	best_number: BlockNumber,
+	accounts: Vec<AccountState>,
+	/// Listed kitties not yet bought in the round, with their owner and price.
+	listings: Vec<([u8; 32], AccountId, Balance)>,
+}
+
+/// Transactions sent and what became of them.
+#[derive(Default)]
+struct Report {
+	submitted: usize,
+	/// Time from submission to inclusion of every included transaction.
+	latencies: Vec<Duration>,
+	/// Reasons of failed transactions, with how often each occurred.
+	failures: HashMap<String, usize>,
+	/// Time spent waiting for the rounds to be included.
+	elapsed: Duration,
+}
+
+impl LoadKittiesCmd {
+	/// Sends the load and prints the report.
+	pub fn run(&self) -> sc_cli::Result<()> {
+		let weights = [
+			(Operation::Mint, self.mints),
+			(Operation::List, self.listings),
+			(Operation::Transfer, self.transfers),
+			(Operation::Buy, self.buys),
+		];
+		if weights.iter().all(|(_, weight)| *weight == 0) {
+			return Err("At least one of the operations needs a non-zero share".into());
+		}
+
+		let runtime = sc_cli::build_runtime()?;
+		runtime.block_on(async {
+			let client = WsClientBuilder::default()
+				.build(&self.url)
+				.await
+				.map_err(|e| format!("Unable to connect to {}: {e}", self.url))?;
+			let genesis_hash: Hash =
+				client.request("chain_getBlockHash", rpc_params![0]).await.map_err(rpc_error)?;
+
+			let mut report = Report::default();
+			for round in 0..self.rounds {
+				let mut snapshot = self.snapshot(&client, genesis_hash).await?;
+				let extrinsics = self.plan(round, &weights, &mut snapshot);
+				let timeout = Duration::from_secs(self.timeout);
+
+				let started = Instant::now();
+				let results = future::join_all(
+					extrinsics.into_iter().map(|extrinsic| submit(&client, extrinsic, timeout)),
+				)
+				.await;
+				report.elapsed += started.elapsed();
+
+				report.submitted += results.len();
+				for result in results {
+					match result {
+						Ok(latency) => report.latencies.push(latency),
+						Err(reason) => *report.failures.entry(reason).or_default() += 1,
+					}
+				}
+				println!(
+					"Round {}/{}: {} of {} transactions included",
+					round + 1,
+					self.rounds,
+					report.latencies.len(),
+					report.submitted,
+				);
+			}
+
+			report.print();
+			Ok(())
+		})
+	}
+
+	/// Reads the state of the sending accounts and the listed kitties at the best block.
+	async fn snapshot(&self, client: &WsClient, genesis_hash: Hash) -> sc_cli::Result<Snapshot> {
+		let header: Header =
+			client.request("chain_getHeader", rpc_params![]).await.map_err(rpc_error)?;
+		let best_hash = header.hash();
+
+		let mut accounts = Vec::new();
+		for keyring in &DEV_ACCOUNTS[..self.accounts as usize] {
+			let account = keyring.to_account_id();
+			let nonce: u32 = client
+				.request("system_accountNextIndex", rpc_params![account.clone()])
+				.await
+				.map_err(rpc_error)?;
+			let kitties =
+				client.kitties_of(account.clone(), Some(best_hash)).await.map_err(rpc_error)?;
+			let owned = kitties.len();
+			let unlisted =
+				kitties.into_iter().filter(|kitty| kitty.price.is_none()).map(|kitty| kitty.dna.0);
+			accounts.push(AccountState { account, nonce, owned, unlisted: unlisted.collect() });
+		}
+
+		// There are never more buys in a round than transactions.
+		let wanted = self.accounts as usize * self.per_account as usize;
+		let mut listings = Vec::new();
+		let mut start = None;
+		while listings.len() < wanted {
+			let page = client
+				.listed_kitties(start, Some(LISTINGS_PAGE_SIZE), Some(best_hash))
+				.await
+				.map_err(rpc_error)?;
+			for kitty in page.kitties {
+				let owner = kitty.owner.parse::<AccountId>();
+				let price = kitty.price.map(Balance::try_from);
+				if let (Ok(owner), Some(Ok(price))) = (owner, price) {
+					listings.push((kitty.dna.0, owner, price));
+				}
+			}
+			match page.next {
+				Some(next) => start = Some(next),
+				None => break,
+			}
+		}
+
+		Ok(Snapshot { genesis_hash, best_hash, best_number: *header.number(), accounts, listings })
+	}
+
+	/// Chooses and signs the transactions of a round.
+	///
+	/// Every kitty is used at most once per round, and no account is made to own more than
+	/// [`MAX_KITTIES_OWNED`] kitties, so the transactions don't fail because of each other.
+	/// Operations that aren't possible with the state at hand are left out of the draw.
+	fn plan(
+		&self,
+		round: u32,
+		weights: &[(Operation, u32)],
+		snapshot: &mut Snapshot,
+	) -> Vec<Bytes> {
+		let accounts = snapshot.accounts.len();
+		let price = Balance::from(self.price) * UNIT;
+		let mut extrinsics = Vec::new();
+
+		for index in 0..self.per_account {
+			for (sender, keyring) in DEV_ACCOUNTS.iter().enumerate().take(accounts) {
+				let recipient = (sender + 1) % accounts;
+				let buyable = snapshot
+					.listings
+					.iter()
+					.position(|(_, owner, _)| *owner != snapshot.accounts[sender].account);
+				let state = &snapshot.accounts[sender];
+				let possible = |operation: &Operation| match operation {
+					Operation::Mint => state.has_room(),
+					Operation::List => !state.unlisted.is_empty(),
+					Operation::Transfer =>
+						!state.unlisted.is_empty() &&
+							recipient != sender && snapshot.accounts[recipient].has_room(),
+					Operation::Buy => buyable.is_some() && state.has_room(),
+				};
+				let candidates: Vec<_> =
+					weights.iter().filter(|(operation, _)| possible(operation)).collect();
+				let total: u64 = candidates.iter().map(|(_, weight)| u64::from(*weight)).sum();
+				if total == 0 {
+					continue;
+				}
+
+				let seed = (self.seed, round, index, sender as u32).encode();
+				let roll = u64::from_le_bytes(
+					blake2_256(&seed)[..8].try_into().expect("a hash has more than 8 bytes; qed"),
+				);
+				let mut roll = roll % total;
+				let operation = candidates
+					.iter()
+					.find(|(_, weight)| {
+						let hit = roll < u64::from(*weight);
+						roll = roll.saturating_sub(u64::from(*weight));
+						hit
+					})
+					.map(|(operation, _)| *operation)
+					.expect("the roll is below the sum of the weights; qed");
+
+				let call: RuntimeCall = match operation {
+					Operation::Mint => {
+						snapshot.accounts[sender].owned += 1;
+						TemplateCall::create_kitty {}.into()
+					},
+					Operation::List => {
+						let kitty_id =
+							snapshot.accounts[sender].unlisted.pop().expect("checked above");
+						TemplateCall::set_price { kitty_id, price: Some(price) }.into()
+					},
+					Operation::Transfer => {
+						let kitty_id =
+							snapshot.accounts[sender].unlisted.pop().expect("checked above");
+						snapshot.accounts[recipient].owned += 1;
+						let to = snapshot.accounts[recipient].account.clone();
+						TemplateCall::transfer { to, kitty_id }.into()
+					},
+					Operation::Buy => {
+						let listing = buyable.expect("checked above");
+						let (kitty_id, _, max_price) = snapshot.listings.swap_remove(listing);
+						snapshot.accounts[sender].owned += 1;
+						TemplateCall::buy_kitty { kitty_id, max_price }.into()
+					},
+				};
+
+				let state = &mut snapshot.accounts[sender];
+				let extrinsic = sign_extrinsic(
+					keyring.pair(),
+					call,
+					state.nonce,
+					snapshot.genesis_hash,
+					snapshot.best_hash,
+					snapshot.best_number,
+				);
+				state.nonce += 1;
+				extrinsics.push(extrinsic.encode().into());
+			}
+		}
+		extrinsics
+	}
+}
+
+/// Submits `extrinsic` and waits until it is included in a block.
+///
+/// Returns the time it took, or why the transaction didn't make it.
+async fn submit(
+	client: &WsClient,
+	extrinsic: Bytes,
+	timeout: Duration,
+) -> Result<Duration, String> {
+	let started = Instant::now();
+	let inclusion = async {
+		let mut statuses = client
+			.subscribe::<TransactionStatus<Hash, Hash>, _>(
+				"author_submitAndWatchExtrinsic",
+				rpc_params![extrinsic],
+				"author_unwatchExtrinsic",
+			)
+			.await
+			.map_err(|e| e.to_string())?;
+		while let Some(status) = statuses.next().await {
+			match status.map_err(|e| e.to_string())? {
+				TransactionStatus::InBlock(_) => return Ok(started.elapsed()),
+				TransactionStatus::Future |
+				TransactionStatus::Ready |
+				TransactionStatus::Broadcast(_) |
+				TransactionStatus::Retracted(_) => {},
+				status => return Err(format!("{status:?}")),
+			}
+		}
+		Err("Subscription closed".into())
+	};
+
+	futures::pin_mut!(inclusion);
+	match future::select(inclusion, futures_timer::Delay::new(timeout)).await {
+		future::Either::Left((result, _)) => result,
+		future::Either::Right(_) => Err("Timed out".into()),
+	}
+}
+
+fn rpc_error(e: jsonrpsee::core::ClientError) -> sc_cli::Error {
+	format!("RPC request failed: {e}").into()
+}
+
+impl Report {
+	fn print(mut self) {
+		let included = self.latencies.len();
+		let seconds = self.elapsed.as_secs_f64();
+		println!(
+			"Submitted {} transactions, {} included, {} failed",
+			self.submitted,
+			included,
+			self.submitted - included,
+		);
+		if seconds > 0.0 {
+			println!("Throughput: {:.2} TPS over {:.1}s", included as f64 / seconds, seconds);
+		}
+
+		self.latencies.sort();
+		if let (Some(min), Some(max)) = (self.latencies.first(), self.latencies.last()) {
+			let percentile = |p: usize| self.latencies[(included - 1) * p / 100].as_millis();
+			let mean = self.latencies.iter().sum::<Duration>() / included as u32;
+			println!(
+				"Inclusion latency: min {}ms, mean {}ms, p50 {}ms, p95 {}ms, max {}ms",
+				min.as_millis(),
+				mean.as_millis(),
+				percentile(50),
+				percentile(95),
+				max.as_millis(),
+			);
+		}
+
+		let mut failures: Vec<_> = self.failures.into_iter().collect();
+		failures.sort_by_key(|(_, count)| Reverse(*count));
+		for (reason, count) in failures {
+			println!("Failed {count}x: {reason}");
+		}
+	}
+}
@@ -0,0 +1,266 @@
+//! Prometheus metrics about the kitties marketplace.
+//!
+//! All metrics are derived from the marketplace events of imported blocks, as returned by the
+//! kitties runtime API. The counters only count finalized blocks, so they never include blocks
+//! that a reorg retracts, and lag behind the best block until finality catches up. The listings
+//! gauge follows the best block.
+
+use std::{collections::HashSet, sync::Arc};
+
+use futures::StreamExt;
+use pallet_template_runtime_api::{KittiesApi as KittiesRuntimeApi, KittyEvent};
+use sc_client_api::BlockchainEvents;
+use solochain_template_runtime::{opaque::Block, AccountId, Balance, UNIT};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::traits::Block as BlockT;
+use substrate_prometheus_endpoint::{
+	register, Counter, Gauge, PrometheusError, Registry, F64, U64,
+};
+
+/// How many listed kitties to fetch per runtime call when counting the listings.
+const LISTINGS_PAGE_SIZE: u32 = 1024;
+
+/// The marketplace metrics.
+#[derive(Clone)]
+pub struct KittiesMetrics {
+	minted: Counter<U64>,
+	transfers: Counter<U64>,
+	sales: Counter<U64>,
+	sales_volume: Counter<F64>,
+	active_listings: Gauge<U64>,
+}
+
+impl KittiesMetrics {
+	/// Registers the metrics with `registry`.
+	pub fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		Ok(Self {
+			minted: register(
+				Counter::new("kitties_minted_total", "Number of kitties minted")?,
+				registry,
+			)?,
+			transfers: register(
+				Counter::new(
+					"kitties_transfers_total",
+					"Number of kitties transferred by their owner or by other pallets through the \
+					 nonfungible traits, not counting sales",
+				)?,
+				registry,
+			)?,
+			sales: register(
+				Counter::new("kitties_sales_total", "Number of kitties sold")?,
+				registry,
+			)?,
+			sales_volume: register(
+				Counter::new(
+					"kitties_sales_volume_total",
+					"Sum of the prices of all kitties sold, in native units",
+				)?,
+				registry,
+			)?,
+			active_listings: register(
+				Gauge::new("kitties_active_listings", "Number of kitties listed for sale")?,
+				registry,
+			)?,
+		})
+	}
+
+	/// Counts the events of one block.
+	fn observe(&self, events: &[KittyEvent<AccountId, Balance>]) {
+		let (mut transferred, mut sold) = (0u64, 0u64);
+		for event in events {
+			match event {
+				KittyEvent::Created { .. } => self.minted.inc(),
+				KittyEvent::Transferred { .. } => transferred += 1,
+				KittyEvent::Sold { price, .. } => {
+					sold += 1;
+					self.sales_volume.inc_by(*price as f64 / UNIT as f64);
+				},
+				KittyEvent::PriceSet { .. } |
+				KittyEvent::Burned { .. } |
+				KittyEvent::ForceTransferred { .. } |
+				KittyEvent::ForceBurned { .. } |
+				KittyEvent::ForcePriceSet { .. } => {},
+			}
+		}
+		self.sales.inc_by(sold);
+		// Every sale also deposits a `Transferred` event.
+		self.transfers.inc_by(transferred.saturating_sub(sold));
+	}
+
+	/// Sets the listings gauge to the number of kitties in `listings`.
+	fn set_listings(&self, listings: &Listings) {
+		self.active_listings.set(listings.len() as u64);
+	}
+}
+
+/// The kitties listed for sale at the best block, kept up to date from the marketplace events.
+type Listings = HashSet<[u8; 32]>;
+
+/// Fetches the kitties listed at block `hash`, walking over all listings.
+fn scan_listings<C>(client: &C, hash: <Block as BlockT>::Hash) -> Option<Listings>
+where
+	C: ProvideRuntimeApi<Block>,
+	C::Api: KittiesRuntimeApi<Block, AccountId, Balance>,
+{
+	let mut listings = Listings::new();
+	let mut start = None;
+	loop {
+		let page = match client.runtime_api().listed_kitties(hash, start, LISTINGS_PAGE_SIZE) {
+			Ok(page) => page,
+			Err(e) => {
+				log::warn!("Unable to fetch the listed kitties of block {hash:?}: {e}");
+				return None;
+			},
+		};
+		listings.extend(page.iter().map(|kitty| kitty.dna));
+		match page.last() {
+			Some(last) if page.len() == LISTINGS_PAGE_SIZE as usize => start = Some(last.dna),
+			_ => break,
+		}
+	}
+	Some(listings)
+}
+
+/// Applies the listing changes of one block's `events` to `listings`.
+///
+/// Sales, and transfers through the nonfungible traits, deposit a `PriceSet` event when they remove
+/// a listing, so besides the price changes only burns and forced transfers need handling.
+fn apply_listing_changes(listings: &mut Listings, events: &[KittyEvent<AccountId, Balance>]) {
+	for event in events {
+		match event {
+			KittyEvent::PriceSet { kitty_id, new_price, .. } |
+			KittyEvent::ForcePriceSet { kitty_id, new_price, .. } => {
+				if new_price.is_some() {
+					listings.insert(*kitty_id);
+				} else {
+					listings.remove(kitty_id);
+				}
+			},
+			KittyEvent::Burned { kitty_id, .. } |
+			KittyEvent::ForceBurned { kitty_id, .. } |
+			KittyEvent::ForceTransferred { kitty_id, .. } => {
+				listings.remove(kitty_id);
+			},
+			KittyEvent::Created { .. } |
+			KittyEvent::Transferred { .. } |
+			KittyEvent::Sold { .. } => {},
+		}
+	}
+}
+
+/// Keeps `metrics` up to date with the blocks imported and finalized by `client`.
+pub async fn run<C>(client: Arc<C>, metrics: KittiesMetrics)
+where
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + BlockchainEvents<Block>,
+	C::Api: KittiesRuntimeApi<Block, AccountId, Balance>,
+{
+	futures::join!(count_finalized(&*client, &metrics), follow_listings(&*client, &metrics));
+}
+
+/// Counts the events of every block finalized since startup.
+///
+/// No finality notifications are sent during a major sync, so a notification only says that
+/// finality moved. The blocks are read off the finalized chain from the last counted one instead.
+async fn count_finalized<C>(client: &C, metrics: &KittiesMetrics)
+where
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + BlockchainEvents<Block>,
+	C::Api: KittiesRuntimeApi<Block, AccountId, Balance>,
+{
+	let mut counted = client.info().finalized_number;
+	let mut finalized = client.finality_notification_stream();
+	while finalized.next().await.is_some() {
+		let finalized_number = client.info().finalized_number;
+		while counted < finalized_number {
+			counted += 1;
+			let hash = match client.hash(counted) {
+				Ok(Some(hash)) => hash,
+				Ok(None) => {
+					log::warn!("Finalized block #{counted} is missing from the database");
+					continue;
+				},
+				Err(e) => {
+					log::warn!("Unable to look up finalized block #{counted}: {e}");
+					continue;
+				},
+			};
+			match client.runtime_api().kitty_events(hash) {
+				Ok(events) => metrics.observe(&events),
+				Err(e) => log::warn!("Unable to read kitty events of block {hash:?}: {e}"),
+			}
+		}
+	}
+}
+
+/// Keeps the listings gauge at the number of kitties listed at the best block.
+///
+/// The listings are fetched once at startup, and again whenever a new best block doesn't build on
+/// the last one followed. Otherwise they follow the events of the new best blocks.
+async fn follow_listings<C>(client: &C, metrics: &KittiesMetrics)
+where
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + BlockchainEvents<Block>,
+	C::Api: KittiesRuntimeApi<Block, AccountId, Balance>,
+{
+	let mut best = client.info().best_hash;
+	let mut listings = scan_listings(client, best);
+	if let Some(listings) = &listings {
+		metrics.set_listings(listings);
+	}
+
+	let mut imports = client.import_notification_stream();
+	while let Some(notification) = imports.next().await {
+		if !notification.is_new_best {
+			continue;
+		}
+		// The events of retracted blocks would have to be undone, and blocks imported during a
+		// major sync or from a file send no notifications, so start over after a reorg or a gap.
+		if notification.tree_route.is_some() || notification.header.parent_hash != best {
+			listings = None;
+		}
+		best = notification.hash;
+		let events = match client.runtime_api().kitty_events(notification.hash) {
+			Ok(events) => events,
+			Err(e) => {
+				log::warn!("Unable to read kitty events of block {:?}: {e}", notification.hash);
+				// The listings can't follow a block whose events are unknown.
+				listings = None;
+				continue;
+			},
+		};
+		match &mut listings {
+			Some(listings) => apply_listing_changes(listings, &events),
+			None => listings = scan_listings(client, notification.hash),
+		}
+		if let Some(listings) = &listings {
+			metrics.set_listings(listings);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn price_set(kitty_id: [u8; 32], new_price: Option<Balance>) -> KittyEvent<AccountId, Balance> {
+		KittyEvent::PriceSet { owner: AccountId::new([0; 32]), kitty_id, new_price }
+	}
+
+	#[test]
+	fn listings_follow_events() {
+		let mut listings = Listings::new();
+		apply_listing_changes(
+			&mut listings,
+			&[price_set([1; 32], Some(10)), price_set([2; 32], Some(20)), price_set([1; 32], Some(5))],
+		);
+		assert_eq!(listings, Listings::from([[1; 32], [2; 32]]));
+
+		apply_listing_changes(
+			&mut listings,
+			&[
+				price_set([1; 32], None),
+				KittyEvent::ForceBurned { owner: AccountId::new([0; 32]), kitty_id: [2; 32] },
+			],
+		);
+		assert!(listings.is_empty());
+	}
+}
@@ -0,0 +1,635 @@
+//! The `kitties_*` RPC namespace.
+//!
+//! Wraps the kitties runtime API and returns JSON-friendly views of the kitties, so that clients
+//! don't have to decode the pallet's storage themselves.
+
+use std::sync::Arc;
+
+use futures::{future, stream, StreamExt};
+use jsonrpsee::{
+	core::RpcResult,
+	proc_macros::rpc,
+	types::error::{ErrorObject, ErrorObjectOwned},
+	PendingSubscriptionSink,
+};
+use pallet_template_runtime_api::{
+	KittiesApi as KittiesRuntimeApi, KittyEvent, KittyInfo, KittyTraits,
+};
+use sc_client_api::BlockchainEvents;
+use sc_rpc::{
+	utils::{spawn_subscription_task, BoundedVecDeque, PendingSubscription},
+	SubscriptionTaskExecutor,
+};
+use serde::{Deserialize, Serialize};
+use solochain_template_runtime::{opaque::Block, AccountId, Balance, BlockNumber};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::{crypto::Ss58Codec, H256};
+use sp_rpc::number::NumberOrHex;
+use sp_runtime::traits::Block as BlockT;
+
+/// The number of kitties returned per page when the caller doesn't ask for a specific amount.
+const DEFAULT_PAGE_SIZE: u32 = 50;
+/// The largest page a caller may request.
+const MAX_PAGE_SIZE: u32 = 500;
+
+/// The decoded traits of a kitty.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KittyTraitsJson {
+	/// Body colour as `#rrggbb`.
+	pub body_color: String,
+	/// Accent colour as `#rrggbb`.
+	pub accent_color: String,
+	/// Eye colour as `#rrggbb`.
+	pub eye_color: String,
+	/// Coat pattern.
+	pub pattern: String,
+	/// Ear shape.
+	pub ear_shape: String,
+	/// Worn accessory, `none` if there is none.
+	pub accessory: String,
+}
+
+impl From<KittyTraits> for KittyTraitsJson {
+	fn from(traits: KittyTraits) -> Self {
+		let color = |[r, g, b]: [u8; 3]| format!("#{r:02x}{g:02x}{b:02x}");
+		Self {
+			body_color: color(traits.body_color),
+			accent_color: color(traits.accent_color),
+			eye_color: color(traits.eye_color),
+			pattern: traits.pattern.as_str().into(),
+			ear_shape: traits.ear_shape.as_str().into(),
+			accessory: traits.accessory.as_str().into(),
+		}
+	}
+}
+
+/// A kitty as returned by the `kitties_*` RPCs.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KittyJson {
+	/// The DNA of the kitty, hex encoded. This is also its id.
+	pub dna: H256,
+	/// The SS58 address of the owner.
+	pub owner: String,
+	/// The asking price, if the kitty is listed for sale.
+	pub price: Option<NumberOrHex>,
+	/// The traits decoded from the DNA.
+	pub traits: KittyTraitsJson,
+}
+
+impl From<KittyInfo<AccountId, Balance>> for KittyJson {
+	fn from(kitty: KittyInfo<AccountId, Balance>) -> Self {
+		Self {
+			dna: H256(kitty.dna),
+			owner: kitty.owner.to_ss58check(),
+			price: kitty.price.map(Into::into),
+			traits: KittyTraits::from_dna(&kitty.dna).into(),
+		}
+	}
+}
+
+/// A single trait of a kitty in [`KittyMetadata`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KittyAttribute {
+	pub trait_type: String,
+	pub value: String,
+}
+
+/// ERC-721 style metadata of a kitty, as understood by common NFT tooling.
+///
+/// Field names follow the ERC-721 metadata JSON schema and the OpenSea extensions of it, rather
+/// than the camel case used by the other `kitties_*` RPCs.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KittyMetadata {
+	pub name: String,
+	pub description: String,
+	/// The SVG image of the kitty as a `data:` URI.
+	pub image: String,
+	pub attributes: Vec<KittyAttribute>,
+}
+
+impl KittyMetadata {
+	/// Builds the metadata of the kitty with the given DNA from its rendered `svg` image.
+	fn new(dna: [u8; 32], svg: &str) -> Self {
+		let traits = KittyTraitsJson::from(KittyTraits::from_dna(&dna));
+		let attribute = |trait_type: &str, value: String| KittyAttribute {
+			trait_type: trait_type.into(),
+			value,
+		};
+		Self {
+			name: format!("Kitty {}", hex::encode(&dna[..4])),
+			description: format!("A kitty with DNA 0x{}.", hex::encode(dna)),
+			image: svg_data_uri(svg),
+			attributes: vec![
+				attribute("Body Color", traits.body_color),
+				attribute("Accent Color", traits.accent_color),
+				attribute("Eye Color", traits.eye_color),
+				attribute("Pattern", traits.pattern),
+				attribute("Ears", traits.ear_shape),
+				attribute("Accessory", traits.accessory),
+			],
+		}
+	}
+}
+
+/// Percent-encodes `svg` into a `data:image/svg+xml` URI.
+fn svg_data_uri(svg: &str) -> String {
+	let mut uri = String::from("data:image/svg+xml;charset=utf-8,");
+	for byte in svg.bytes() {
+		if byte.is_ascii_alphanumeric() || b"-_.~!*'()/:;=,?@+$".contains(&byte) {
+			uri.push(byte as char);
+		} else {
+			uri.push_str(&format!("%{byte:02X}"));
+		}
+	}
+	uri
+}
+
+/// A page of kitties.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KittyPage {
+	/// The kitties on this page.
+	pub kitties: Vec<KittyJson>,
+	/// Pass this as `start` to fetch the next page. `None` if this is the last page.
+	pub next: Option<H256>,
+}
+
+/// The blocks an event subscription follows.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum EventSource {
+	/// Every new best block. Events of blocks retracted by a reorg are sent again, flagged as
+	/// `retracted`.
+	#[default]
+	Best,
+	/// Finalized blocks only.
+	Finalized,
+}
+
+/// Restricts an event subscription to the events a client is interested in.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EventFilter {
+	/// Which blocks to take events from.
+	pub source: EventSource,
+	/// Only send events this account takes part in.
+	pub account: Option<AccountId>,
+	/// Only send events about this kitty.
+	pub kitty_id: Option<H256>,
+}
+
+impl EventFilter {
+	fn matches(&self, event: &KittyEvent<AccountId, Balance>) -> bool {
+		self.account.as_ref().is_none_or(|who| event.involves(who)) &&
+			self.kitty_id.is_none_or(|kitty_id| event.kitty_id() == kitty_id.0)
+	}
+}
+
+/// A marketplace event.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum KittyEventKind {
+	/// A kitty was minted.
+	Created {
+		/// SS58 address of the owner.
+		owner: String,
+		/// DNA of the kitty.
+		kitty_id: H256,
+	},
+	/// A kitty changed hands.
+	Transferred {
+		/// SS58 address of the previous owner.
+		from: String,
+		/// SS58 address of the new owner.
+		to: String,
+		/// DNA of the kitty.
+		kitty_id: H256,
+	},
+	/// A kitty was listed, relisted or delisted.
+	PriceSet {
+		/// SS58 address of the owner.
+		owner: String,
+		/// DNA of the kitty.
+		kitty_id: H256,
+		/// The new asking price, `None` if the kitty was delisted.
+		new_price: Option<NumberOrHex>,
+	},
+	/// A kitty was bought.
+	Sold {
+		/// SS58 address of the seller.
+		seller: String,
+		/// SS58 address of the buyer.
+		buyer: String,
+		/// DNA of the kitty.
+		kitty_id: H256,
+		/// The price paid.
+		price: NumberOrHex,
+	},
+	/// A kitty was destroyed by its owner or by another pallet.
+	Burned {
+		/// SS58 address of the last owner.
+		owner: String,
+		/// DNA of the kitty.
+		kitty_id: H256,
+	},
+	/// A kitty was moved to a new owner by the force origin. Any listing was removed.
+	ForceTransferred {
+		/// SS58 address of the previous owner.
+		from: String,
+		/// SS58 address of the new owner.
+		to: String,
+		/// DNA of the kitty.
+		kitty_id: H256,
+	},
+	/// A kitty was destroyed by the force origin.
+	ForceBurned {
+		/// SS58 address of the last owner.
+		owner: String,
+		/// DNA of the kitty.
+		kitty_id: H256,
+	},
+	/// The price of a kitty was changed by the force origin.
+	ForcePriceSet {
+		/// SS58 address of the owner.
+		owner: String,
+		/// DNA of the kitty.
+		kitty_id: H256,
+		/// The new asking price, `None` if the kitty was delisted.
+		new_price: Option<NumberOrHex>,
+	},
+}
+
+impl From<KittyEvent<AccountId, Balance>> for KittyEventKind {
+	fn from(event: KittyEvent<AccountId, Balance>) -> Self {
+		match event {
+			KittyEvent::Created { owner, kitty_id } =>
+				Self::Created { owner: owner.to_ss58check(), kitty_id: H256(kitty_id) },
+			KittyEvent::Transferred { from, to, kitty_id } => Self::Transferred {
+				from: from.to_ss58check(),
+				to: to.to_ss58check(),
+				kitty_id: H256(kitty_id),
+			},
+			KittyEvent::PriceSet { owner, kitty_id, new_price } => Self::PriceSet {
+				owner: owner.to_ss58check(),
+				kitty_id: H256(kitty_id),
+				new_price: new_price.map(Into::into),
+			},
+			KittyEvent::Sold { seller, buyer, kitty_id, price } => Self::Sold {
+				seller: seller.to_ss58check(),
+				buyer: buyer.to_ss58check(),
+				kitty_id: H256(kitty_id),
+				price: price.into(),
+			},
+			KittyEvent::Burned { owner, kitty_id } =>
+				Self::Burned { owner: owner.to_ss58check(), kitty_id: H256(kitty_id) },
+			KittyEvent::ForceTransferred { from, to, kitty_id } => Self::ForceTransferred {
+				from: from.to_ss58check(),
+				to: to.to_ss58check(),
+				kitty_id: H256(kitty_id),
+			},
+			KittyEvent::ForceBurned { owner, kitty_id } =>
+				Self::ForceBurned { owner: owner.to_ss58check(), kitty_id: H256(kitty_id) },
+			KittyEvent::ForcePriceSet { owner, kitty_id, new_price } => Self::ForcePriceSet {
+				owner: owner.to_ss58check(),
+				kitty_id: H256(kitty_id),
+				new_price: new_price.map(Into::into),
+			},
+		}
+	}
+}
+
+/// A marketplace event together with the block it was deposited in.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KittyEventJson {
+	/// Hash of the block that deposited the event.
+	pub block_hash: H256,
+	/// Number of the block that deposited the event.
+	pub block_number: BlockNumber,
+	/// Set if the block was retracted by a reorg, i.e. the event no longer happened.
+	pub retracted: bool,
+	/// The event itself.
+	#[serde(flatten)]
+	pub event: KittyEventKind,
+}
+
+/// Kitties RPC methods.
+#[rpc(client, server)]
+pub trait KittiesApi<BlockHash> {
+	/// The kitty with the given DNA, if it exists.
+	#[method(name = "kitties_getKitty")]
+	fn kitty(&self, dna: H256, at: Option<BlockHash>) -> RpcResult<Option<KittyJson>>;
+
+	/// All kitties owned by `owner`.
+	#[method(name = "kitties_kittiesOf")]
+	fn kitties_of(&self, owner: AccountId, at: Option<BlockHash>) -> RpcResult<Vec<KittyJson>>;
+
+	/// The total number of kitties.
+	#[method(name = "kitties_count")]
+	fn kitty_count(&self, at: Option<BlockHash>) -> RpcResult<u32>;
+
+	/// A page of the kitties that are listed for sale, starting after `start`.
+	#[method(name = "kitties_listed")]
+	fn listed_kitties(
+		&self,
+		start: Option<H256>,
+		limit: Option<u32>,
+		at: Option<BlockHash>,
+	) -> RpcResult<KittyPage>;
+
+	/// The image of the kitty with the given DNA as an SVG document, if the kitty exists.
+	///
+	/// The image is rendered by the runtime, so it is the same on every node.
+	#[method(name = "kitties_image")]
+	fn kitty_image(&self, dna: H256, at: Option<BlockHash>) -> RpcResult<Option<String>>;
+
+	/// ERC-721 style metadata of the kitty with the given DNA, if the kitty exists.
+	#[method(name = "kitties_metadata")]
+	fn kitty_metadata(&self, dna: H256, at: Option<BlockHash>) -> RpcResult<Option<KittyMetadata>>;
+
+	/// Streams marketplace events as blocks are imported or finalized.
+	#[subscription(
+		name = "kitties_subscribeEvents" => "kitties_event",
+		unsubscribe = "kitties_unsubscribeEvents",
+		item = KittyEventJson,
+	)]
+	fn subscribe_events(&self, filter: Option<EventFilter>);
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The call to the runtime failed.
+	RuntimeError,
+	/// The requested page is larger than [`MAX_PAGE_SIZE`].
+	PageTooLarge,
+}
+
+impl From<Error> for i32 {
+	fn from(e: Error) -> i32 {
+		match e {
+			Error::RuntimeError => 1,
+			Error::PageTooLarge => 2,
+		}
+	}
+}
+
+fn runtime_error(e: impl std::fmt::Display) -> ErrorObjectOwned {
+	ErrorObject::owned(Error::RuntimeError.into(), "Unable to query kitties.", Some(e.to_string()))
+}
+
+/// The events of block `hash` that pass `filter`.
+fn block_events<C>(
+	client: &C,
+	hash: <Block as BlockT>::Hash,
+	retracted: bool,
+	filter: &EventFilter,
+) -> Vec<KittyEventJson>
+where
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: KittiesRuntimeApi<Block, AccountId, Balance>,
+{
+	let block_number = match client.number(hash) {
+		Ok(Some(number)) => number,
+		_ => return Vec::new(),
+	};
+	let events = match client.runtime_api().kitty_events(hash) {
+		Ok(events) => events,
+		Err(e) => {
+			log::warn!("Unable to read kitty events of block {hash:?}: {e}");
+			return Vec::new();
+		},
+	};
+
+	events
+		.into_iter()
+		.filter(|event| filter.matches(event))
+		.map(|event| KittyEventJson {
+			block_hash: hash,
+			block_number,
+			retracted,
+			event: event.into(),
+		})
+		.collect()
+}
+
+/// Provides the `kitties_*` RPCs on top of the kitties runtime API.
+pub struct KittiesRpc<C> {
+	client: Arc<C>,
+	executor: SubscriptionTaskExecutor,
+}
+
+impl<C> KittiesRpc<C> {
+	/// Creates a new instance of the kitties RPC handler.
+	pub fn new(client: Arc<C>, executor: SubscriptionTaskExecutor) -> Self {
+		Self { client, executor }
+	}
+}
+
+impl<C> KittiesApiServer<<Block as BlockT>::Hash> for KittiesRpc<C>
+where
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + BlockchainEvents<Block>,
+	C: Send + Sync + 'static,
+	C::Api: KittiesRuntimeApi<Block, AccountId, Balance>,
+{
+	fn kitty(
+		&self,
+		dna: H256,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Option<KittyJson>> {
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		let kitty = self.client.runtime_api().kitty(at, dna.0).map_err(runtime_error)?;
+		Ok(kitty.map(Into::into))
+	}
+
+	fn kitties_of(
+		&self,
+		owner: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Vec<KittyJson>> {
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		let kitties = self.client.runtime_api().kitties_of(at, owner).map_err(runtime_error)?;
+		Ok(kitties.into_iter().map(Into::into).collect())
+	}
+
+	fn kitty_count(&self, at: Option<<Block as BlockT>::Hash>) -> RpcResult<u32> {
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		self.client.runtime_api().kitty_count(at).map_err(runtime_error)
+	}
+
+	fn listed_kitties(
+		&self,
+		start: Option<H256>,
+		limit: Option<u32>,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<KittyPage> {
+		let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE);
+		if limit > MAX_PAGE_SIZE {
+			return Err(ErrorObject::owned(
+				Error::PageTooLarge.into(),
+				format!("A page may contain at most {MAX_PAGE_SIZE} kitties."),
+				Some(limit),
+			));
+		}
+
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		let kitties = self
+			.client
+			.runtime_api()
+			.listed_kitties(at, start.map(|dna| dna.0), limit)
+			.map_err(runtime_error)?;
+
+		// A short page means there is nothing left to fetch.
+		let next = match kitties.last() {
+			Some(kitty) if kitties.len() == limit as usize => Some(H256(kitty.dna)),
+			_ => None,
+		};
+		Ok(KittyPage { kitties: kitties.into_iter().map(Into::into).collect(), next })
+	}
+
+	fn kitty_image(
+		&self,
+		dna: H256,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Option<String>> {
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		let image = self.client.runtime_api().kitty_image(at, dna.0).map_err(runtime_error)?;
+		image.map(|svg| String::from_utf8(svg).map_err(runtime_error)).transpose()
+	}
+
+	fn kitty_metadata(
+		&self,
+		dna: H256,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Option<KittyMetadata>> {
+		let svg = self.kitty_image(dna, at)?;
+		Ok(svg.map(|svg| KittyMetadata::new(dna.0, &svg)))
+	}
+
+	fn subscribe_events(&self, pending: PendingSubscriptionSink, filter: Option<EventFilter>) {
+		let filter = filter.unwrap_or_default();
+
+		// Every notification turns into the list of blocks whose events should be sent, each
+		// flagged with whether it was retracted.
+		let blocks = match filter.source {
+			EventSource::Best => self
+				.client
+				.import_notification_stream()
+				.filter(|notification| future::ready(notification.is_new_best))
+				.map(|notification| {
+					let mut blocks = Vec::new();
+					if let Some(route) = &notification.tree_route {
+						blocks.extend(route.retracted().iter().map(|block| (block.hash, true)));
+						blocks.extend(route.enacted().iter().map(|block| (block.hash, false)));
+					}
+					blocks.push((notification.hash, false));
+					blocks
+				})
+				.boxed(),
+			EventSource::Finalized => self
+				.client
+				.finality_notification_stream()
+				.map(|notification| {
+					notification
+						.tree_route
+						.iter()
+						.copied()
+						.chain(std::iter::once(notification.hash))
+						.map(|hash| (hash, false))
+						.collect::<Vec<_>>()
+				})
+				.boxed(),
+		};
+
+		let client = self.client.clone();
+		let events = blocks
+			.flat_map(move |blocks| {
+				let events = blocks
+					.into_iter()
+					.flat_map(|(hash, retracted)| block_events(&*client, hash, retracted, &filter))
+					.collect::<Vec<_>>();
+				stream::iter(events)
+			})
+			.boxed();
+
+		spawn_subscription_task(
+			&self.executor,
+			PendingSubscription::from(pending).pipe_from_stream(events, BoundedVecDeque::default()),
+		);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const ALICE: AccountId = AccountId::new([1; 32]);
+	const BOB: AccountId = AccountId::new([2; 32]);
+	const CHARLIE: AccountId = AccountId::new([3; 32]);
+
+	fn filter(account: Option<AccountId>, kitty_id: Option<[u8; 32]>) -> EventFilter {
+		EventFilter { account, kitty_id: kitty_id.map(H256), ..Default::default() }
+	}
+
+	#[test]
+	fn event_filter_matches() {
+		let event: KittyEvent<AccountId, Balance> =
+			KittyEvent::Transferred { from: ALICE, to: BOB, kitty_id: [7; 32] };
+
+		assert!(filter(None, None).matches(&event));
+		assert!(filter(Some(ALICE), None).matches(&event));
+		assert!(filter(Some(BOB), None).matches(&event));
+		assert!(!filter(Some(CHARLIE), None).matches(&event));
+		assert!(filter(None, Some([7; 32])).matches(&event));
+		assert!(!filter(None, Some([8; 32])).matches(&event));
+		// Both restrictions have to hold.
+		assert!(filter(Some(BOB), Some([7; 32])).matches(&event));
+		assert!(!filter(Some(BOB), Some([8; 32])).matches(&event));
+		assert!(!filter(Some(CHARLIE), Some([7; 32])).matches(&event));
+	}
+
+	#[test]
+	fn event_filter_matches_both_sides_of_a_sale() {
+		let event: KittyEvent<AccountId, Balance> =
+			KittyEvent::Sold { seller: ALICE, buyer: BOB, kitty_id: [7; 32], price: 10 };
+
+		assert!(filter(Some(ALICE), None).matches(&event));
+		assert!(filter(Some(BOB), None).matches(&event));
+		assert!(!filter(Some(CHARLIE), None).matches(&event));
+	}
+
+	#[test]
+	fn svg_data_uri_percent_encodes_reserved_characters() {
+		assert_eq!(
+			svg_data_uri(r##"<rect fill="#0a0b0c"/> 50%"##),
+			"data:image/svg+xml;charset=utf-8,%3Crect%20fill=%22%230a0b0c%22/%3E%2050%25",
+		);
+		assert_eq!(svg_data_uri("AZaz09-_.~"), "data:image/svg+xml;charset=utf-8,AZaz09-_.~");
+	}
+
+	#[test]
+	fn kitty_metadata_follows_the_erc721_schema() {
+		let mut dna = [0u8; 32];
+		dna[..12].copy_from_slice(&[0x12, 0x34, 0x56, 0xab, 0xcd, 0xef, 1, 2, 3, 2, 2, 255]);
+		let metadata = KittyMetadata::new(dna, "<svg/>");
+
+		assert_eq!(
+			serde_json::to_value(&metadata).unwrap(),
+			serde_json::json!({
+				"name": "Kitty 123456ab",
+				"description": format!("A kitty with DNA 0x123456abcdef0102030202ff{}.", "00".repeat(20)),
+				"image": "data:image/svg+xml;charset=utf-8,%3Csvg/%3E",
+				"attributes": [
+					{ "trait_type": "Body Color", "value": "#123456" },
+					{ "trait_type": "Accent Color", "value": "#abcdef" },
+					{ "trait_type": "Eye Color", "value": "#010203" },
+					{ "trait_type": "Pattern", "value": "spotted" },
+					{ "trait_type": "Ears", "value": "folded" },
+					{ "trait_type": "Accessory", "value": "glasses" },
+				],
+			}),
+		);
+	}
+}
@@ -0,0 +1,204 @@
+//! Decoding of the visual traits encoded in a kitty's DNA.
+//!
+//! The layout is part of the public interface of the chain: clients rely on the same DNA always
+//! decoding to the same traits, so existing byte positions must never be reassigned.
+
+/// The coat pattern of a kitty.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Pattern {
+	Solid,
+	Striped,
+	Spotted,
+	Patched,
+}
+
+/// The shape of a kitty's ears.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EarShape {
+	Pointy,
+	Round,
+	Folded,
+}
+
+/// An optional accessory worn by a kitty.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Accessory {
+	None,
+	Bowtie,
+	Hat,
+	Glasses,
+}
+
+impl Pattern {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Pattern::Solid => "solid",
+			Pattern::Striped => "striped",
+			Pattern::Spotted => "spotted",
+			Pattern::Patched => "patched",
+		}
+	}
+}
+
+impl EarShape {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			EarShape::Pointy => "pointy",
+			EarShape::Round => "round",
+			EarShape::Folded => "folded",
+		}
+	}
+}
+
+impl Accessory {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Accessory::None => "none",
+			Accessory::Bowtie => "bowtie",
+			Accessory::Hat => "hat",
+			Accessory::Glasses => "glasses",
+		}
+	}
+}
+
+/// The traits of a kitty, as decoded from its DNA.
+///
+/// | bytes  | trait          |
+/// |--------|----------------|
+/// | 0..3   | body colour    |
+/// | 3..6   | accent colour  |
+/// | 6..9   | eye colour     |
+/// | 9      | pattern        |
+/// | 10     | ear shape      |
+/// | 11     | accessory      |
+///
+/// The remaining bytes are unused and only guarantee uniqueness.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct KittyTraits {
+	pub body_color: [u8; 3],
+	pub accent_color: [u8; 3],
+	pub eye_color: [u8; 3],
+	pub pattern: Pattern,
+	pub ear_shape: EarShape,
+	pub accessory: Accessory,
+}
+
+impl KittyTraits {
+	pub fn from_dna(dna: &[u8; 32]) -> Self {
+		let pattern = match dna[9] % 4 {
+			0 => Pattern::Solid,
+			1 => Pattern::Striped,
+			2 => Pattern::Spotted,
+			_ => Pattern::Patched,
+		};
+		let ear_shape = match dna[10] % 3 {
+			0 => EarShape::Pointy,
+			1 => EarShape::Round,
+			_ => EarShape::Folded,
+		};
+		// Accessories are rare: only a quarter of kitties wear one.
+		let accessory = match dna[11] {
+			0..=191 => Accessory::None,
+			192..=213 => Accessory::Bowtie,
+			214..=234 => Accessory::Hat,
+			_ => Accessory::Glasses,
+		};
+
+		Self {
+			body_color: [dna[0], dna[1], dna[2]],
+			accent_color: [dna[3], dna[4], dna[5]],
+			eye_color: [dna[6], dna[7], dna[8]],
+			pattern,
+			ear_shape,
+			accessory,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn from_dna_reads_the_documented_bytes() {
+		let mut dna = [0u8; 32];
+		dna[..12].copy_from_slice(&[10, 20, 30, 40, 50, 60, 70, 80, 90, 5, 4, 200]);
+		dna[12..].fill(0xff);
+
+		assert_eq!(
+			KittyTraits::from_dna(&dna),
+			KittyTraits {
+				body_color: [10, 20, 30],
+				accent_color: [40, 50, 60],
+				eye_color: [70, 80, 90],
+				pattern: Pattern::Striped,
+				ear_shape: EarShape::Round,
+				accessory: Accessory::Bowtie,
+			}
+		);
+	}
+
+	#[test]
+	fn from_dna_covers_every_variant() {
+		let traits = |pattern: u8, ears: u8, accessory: u8| {
+			let mut dna = [0u8; 32];
+			(dna[9], dna[10], dna[11]) = (pattern, ears, accessory);
+			KittyTraits::from_dna(&dna)
+		};
+
+		let patterns: Vec<_> = (0..8).map(|byte| traits(byte, 0, 0).pattern).collect();
+		assert_eq!(
+			patterns,
+			[
+				Pattern::Solid,
+				Pattern::Striped,
+				Pattern::Spotted,
+				Pattern::Patched,
+				Pattern::Solid,
+				Pattern::Striped,
+				Pattern::Spotted,
+				Pattern::Patched,
+			]
+		);
+
+		let ears: Vec<_> =
+			[0, 1, 2, 3, 255].iter().map(|&byte| traits(0, byte, 0).ear_shape).collect();
+		assert_eq!(
+			ears,
+			[
+				EarShape::Pointy,
+				EarShape::Round,
+				EarShape::Folded,
+				EarShape::Pointy,
+				EarShape::Pointy
+			]
+		);
+
+		// The boundaries of the accessory ranges.
+		let accessories: Vec<_> = [0, 191, 192, 213, 214, 234, 235, 255]
+			.iter()
+			.map(|&byte| traits(0, 0, byte).accessory)
+			.collect();
+		assert_eq!(
+			accessories,
+			[
+				Accessory::None,
+				Accessory::None,
+				Accessory::Bowtie,
+				Accessory::Bowtie,
+				Accessory::Hat,
+				Accessory::Hat,
+				Accessory::Glasses,
+				Accessory::Glasses,
+			]
+		);
+	}
+
+	#[test]
+	fn from_dna_ignores_the_unused_bytes() {
+		let mut dna = [7u8; 32];
+		let traits = KittyTraits::from_dna(&dna);
+		dna[12..].copy_from_slice(&[0xab; 20]);
+		assert_eq!(KittyTraits::from_dna(&dna), traits);
+	}
+}
@@ -0,0 +1,113 @@
+//! Runtime API definition for the kitties pallet.
+//!
+//! Clients should query kitties through this API instead of decoding the pallet's raw storage,
+//! so that the storage layout can change without breaking them.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub mod dna;
+pub mod svg;
+
+use alloc::vec::Vec;
+use codec::{Codec, Decode, Encode};
+use scale_info::TypeInfo;
+
+pub use dna::KittyTraits;
+pub use svg::render_svg;
+
+/// A kitty as exposed by [`KittiesApi`].
+///
+/// Mirrors `pallet_template::Kitty`, but is generic over the concrete account and balance types
+/// rather than over the runtime configuration.
+#[derive(Clone, PartialEq, Eq, Debug, Encode, Decode, TypeInfo)]
+pub struct KittyInfo<AccountId, Balance> {
+	/// The unique DNA of the kitty, which is also its id.
+	pub dna: [u8; 32],
+	/// The current owner.
+	pub owner: AccountId,
+	/// The asking price, if the kitty is listed for sale.
+	pub price: Option<Balance>,
+}
+
+/// A marketplace event as exposed by [`KittiesApi`].
+#[derive(Clone, PartialEq, Eq, Debug, Encode, Decode, TypeInfo)]
+pub enum KittyEvent<AccountId, Balance> {
+	/// A kitty was minted.
+	Created { owner: AccountId, kitty_id: [u8; 32] },
+	/// A kitty changed hands.
+	Transferred { from: AccountId, to: AccountId, kitty_id: [u8; 32] },
+	/// A kitty was listed, relisted at a new price or delisted.
+	PriceSet { owner: AccountId, kitty_id: [u8; 32], new_price: Option<Balance> },
+	/// A kitty was bought.
+	Sold { seller: AccountId, buyer: AccountId, kitty_id: [u8; 32], price: Balance },
+	/// A kitty was destroyed by its owner or by another pallet.
+	Burned { owner: AccountId, kitty_id: [u8; 32] },
+	/// A kitty was moved to a new owner by the force origin. Any listing was removed.
+	ForceTransferred { from: AccountId, to: AccountId, kitty_id: [u8; 32] },
+	/// A kitty was destroyed by the force origin.
+	ForceBurned { owner: AccountId, kitty_id: [u8; 32] },
+	/// The price of a kitty was changed by the force origin.
+	ForcePriceSet { owner: AccountId, kitty_id: [u8; 32], new_price: Option<Balance> },
+}
+
+impl<AccountId: PartialEq, Balance> KittyEvent<AccountId, Balance> {
+	/// The kitty the event is about.
+	pub fn kitty_id(&self) -> [u8; 32] {
+		match self {
+			KittyEvent::Created { kitty_id, .. } |
+			KittyEvent::Transferred { kitty_id, .. } |
+			KittyEvent::PriceSet { kitty_id, .. } |
+			KittyEvent::Sold { kitty_id, .. } |
+			KittyEvent::Burned { kitty_id, .. } |
+			KittyEvent::ForceTransferred { kitty_id, .. } |
+			KittyEvent::ForceBurned { kitty_id, .. } |
+			KittyEvent::ForcePriceSet { kitty_id, .. } => *kitty_id,
+		}
+	}
+
+	/// Whether `who` takes part in the event.
+	pub fn involves(&self, who: &AccountId) -> bool {
+		match self {
+			KittyEvent::Created { owner, .. } |
+			KittyEvent::PriceSet { owner, .. } |
+			KittyEvent::Burned { owner, .. } |
+			KittyEvent::ForceBurned { owner, .. } |
+			KittyEvent::ForcePriceSet { owner, .. } => owner == who,
+			KittyEvent::Transferred { from, to, .. } |
+			KittyEvent::ForceTransferred { from, to, .. } => from == who || to == who,
+			KittyEvent::Sold { seller, buyer, .. } => seller == who || buyer == who,
+		}
+	}
+}
+
+sp_api::decl_runtime_apis! {
+	/// Read-only queries over the kitties stored by the pallet.
+	pub trait KittiesApi<AccountId, Balance>
+	where
+		AccountId: Codec,
+		Balance: Codec,
+	{
+		/// The kitty with the given DNA, if it exists.
+		fn kitty(dna: [u8; 32]) -> Option<KittyInfo<AccountId, Balance>>;
+
+		/// All kitties currently owned by `account`.
+		fn kitties_of(account: AccountId) -> Vec<KittyInfo<AccountId, Balance>>;
+
+		/// The total number of kitties in existence.
+		fn kitty_count() -> u32;
+
+		/// Up to `limit` kitties that are listed for sale.
+		///
+		/// Pass the DNA of the last kitty of the previous page as `start` to continue from there.
+		fn listed_kitties(start: Option<[u8; 32]>, limit: u32) -> Vec<KittyInfo<AccountId, Balance>>;
+
+		/// The marketplace events deposited by the block this is called at.
+		fn kitty_events() -> Vec<KittyEvent<AccountId, Balance>>;
+
+		/// The image of the kitty with the given DNA as a UTF-8 encoded SVG document, if the kitty
+		/// exists. See [`render_svg`].
+		fn kitty_image(dna: [u8; 32]) -> Option<Vec<u8>>;
+	}
+}
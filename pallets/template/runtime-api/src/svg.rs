@@ -0,0 +1,141 @@
+//! Rendering of kitties as SVG images.
+//!
+//! The image only depends on the traits decoded from the DNA, so every client renders the same
+//! kitty the same way. Like the DNA layout, the art is part of the public interface of the chain:
+//! changing it changes what every kitty looks like.
+
+use alloc::{format, string::String};
+use core::fmt::Write;
+
+use crate::dna::{Accessory, EarShape, KittyTraits, Pattern};
+
+/// Width and height of the rendered image, in pixels.
+pub const IMAGE_SIZE: u32 = 256;
+
+const BACKGROUND: &str = "#f5f0e8";
+const OUTLINE: &str = "#2b2b2b";
+const NOSE: &str = "#e88a9a";
+const EYE_WHITE: &str = "#ffffff";
+
+fn color([r, g, b]: [u8; 3]) -> String {
+	format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+/// Renders the kitty with the given DNA as a standalone SVG document.
+pub fn render_svg(dna: &[u8; 32]) -> String {
+	let traits = KittyTraits::from_dna(dna);
+	let body = color(traits.body_color);
+	let accent = color(traits.accent_color);
+	let eyes = color(traits.eye_color);
+
+	let mut svg = String::new();
+	// Writing to a `String` cannot fail.
+	let _ = write!(
+		svg,
+		r#"<svg xmlns="http://www.w3.org/2000/svg" width="{IMAGE_SIZE}" height="{IMAGE_SIZE}" viewBox="0 0 256 256">"#
+	);
+	let _ = write!(svg, r#"<rect width="256" height="256" fill="{BACKGROUND}"/>"#);
+
+	// Body, then ears behind the head.
+	let _ = write!(svg, r#"<ellipse cx="128" cy="206" rx="72" ry="50" fill="{body}"/>"#);
+	let _ = match traits.ear_shape {
+		EarShape::Pointy => write!(
+			svg,
+			r#"<polygon points="70,100 84,38 122,78" fill="{body}"/><polygon points="186,100 172,38 134,78" fill="{body}"/>"#
+		),
+		EarShape::Round => write!(
+			svg,
+			r#"<circle cx="82" cy="70" r="26" fill="{body}"/><circle cx="174" cy="70" r="26" fill="{body}"/>"#
+		),
+		EarShape::Folded => write!(
+			svg,
+			r#"<polygon points="70,100 84,56 122,78" fill="{body}"/><polygon points="84,56 104,76 80,76" fill="{accent}"/><polygon points="186,100 172,56 134,78" fill="{body}"/><polygon points="172,56 152,76 176,76" fill="{accent}"/>"#
+		),
+	};
+	let _ = write!(svg, r#"<circle cx="128" cy="120" r="62" fill="{body}"/>"#);
+
+	let _ = match traits.pattern {
+		Pattern::Solid => Ok(()),
+		Pattern::Striped => write!(
+			svg,
+			r#"<path d="M108 64 L112 88 M128 60 L128 86 M148 64 L144 88" stroke="{accent}" stroke-width="6" stroke-linecap="round"/>"#
+		),
+		Pattern::Spotted => write!(
+			svg,
+			r#"<circle cx="98" cy="90" r="9" fill="{accent}"/><circle cx="162" cy="96" r="7" fill="{accent}"/><circle cx="152" cy="154" r="8" fill="{accent}"/>"#
+		),
+		Pattern::Patched =>
+			write!(svg, r#"<ellipse cx="152" cy="112" rx="32" ry="28" fill="{accent}"/>"#),
+	};
+
+	// Face.
+	let _ = write!(
+		svg,
+		r#"<circle cx="104" cy="116" r="14" fill="{EYE_WHITE}"/><circle cx="152" cy="116" r="14" fill="{EYE_WHITE}"/><circle cx="104" cy="118" r="8" fill="{eyes}"/><circle cx="152" cy="118" r="8" fill="{eyes}"/>"#
+	);
+	let _ = write!(
+		svg,
+		r#"<polygon points="120,138 136,138 128,148" fill="{NOSE}"/><path d="M128 148 Q120 158 112 152 M128 148 Q136 158 144 152" stroke="{OUTLINE}" stroke-width="2" fill="none"/>"#
+	);
+
+	let _ = match traits.accessory {
+		Accessory::None => Ok(()),
+		Accessory::Bowtie => write!(
+			svg,
+			r#"<polygon points="128,188 104,176 104,200" fill="{accent}"/><polygon points="128,188 152,176 152,200" fill="{accent}"/><circle cx="128" cy="188" r="5" fill="{OUTLINE}"/>"#
+		),
+		Accessory::Hat => write!(
+			svg,
+			r#"<rect x="92" y="24" width="72" height="40" fill="{accent}"/><rect x="74" y="60" width="108" height="8" fill="{accent}"/>"#
+		),
+		Accessory::Glasses => write!(
+			svg,
+			r#"<g fill="none" stroke="{OUTLINE}" stroke-width="4"><circle cx="104" cy="116" r="19"/><circle cx="152" cy="116" r="19"/><path d="M123 116 L133 116"/></g>"#
+		),
+	};
+
+	svg.push_str("</svg>");
+	svg
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Striped, round ears, bowtie.
+	const DNA: [u8; 32] = {
+		let mut dna = [0u8; 32];
+		(dna[0], dna[1], dna[2]) = (0x12, 0x34, 0x56);
+		(dna[3], dna[4], dna[5]) = (0xab, 0xcd, 0xef);
+		(dna[6], dna[7], dna[8]) = (0x01, 0x02, 0x03);
+		(dna[9], dna[10], dna[11]) = (1, 1, 200);
+		dna
+	};
+
+	#[test]
+	fn render_svg_draws_the_traits() {
+		let svg = render_svg(&DNA);
+
+		assert!(svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="256""#));
+		assert!(svg.ends_with("</svg>"));
+		assert!(svg.contains(r##"<circle cx="128" cy="120" r="62" fill="#123456"/>"##));
+		assert!(svg.contains(r##"<circle cx="104" cy="118" r="8" fill="#010203"/>"##));
+		// The round ears, the stripes and the bowtie.
+		assert!(svg.contains(r##"<circle cx="82" cy="70" r="26" fill="#123456"/>"##));
+		assert!(svg.contains(r##"stroke="#abcdef" stroke-width="6""##));
+		assert!(svg.contains(r##"<polygon points="128,188 104,176 104,200" fill="#abcdef"/>"##));
+		assert!(!svg.contains("<g "));
+	}
+
+	#[test]
+	fn render_svg_only_depends_on_the_traits() {
+		let mut dna = DNA;
+		dna[31] = 0xff;
+		assert_eq!(render_svg(&dna), render_svg(&DNA));
+
+		dna[11] = 0;
+		let plain = render_svg(&dna);
+		assert_ne!(plain, render_svg(&DNA));
+		assert!(!plain.contains("128,188"));
+	}
+}
@@ -5,17 +5,41 @@ use super::*;
 #[allow(unused)]
 use crate::Pallet as Collectables;
 use frame_benchmarking::v2::*;
+use frame_support::pallet_prelude::DispatchResult;
 use frame_support::traits::fungible::Inspect;
 use frame_support::traits::fungible::Mutate;
+use frame_support::traits::EnsureOrigin;
+use frame_support::traits::Get;
 use frame_system::RawOrigin;
+use sp_runtime::Permill;
+
+/// Mints `count` kitties to `owner`. `seed` keeps the DNA of different owners apart.
+fn mint_kitties<T: Config>(owner: &T::AccountId, seed: u8, count: u32) -> DispatchResult {
+    for index in 0..count {
+        let mut dna = [seed; 32];
+        dna[..4].copy_from_slice(&index.to_le_bytes());
+        Pallet::<T>::mint(owner.clone(), dna)?;
+    }
+    Ok(())
+}
 
 #[benchmarks]
 mod benchmarks {
     use super::*;
 
     #[benchmark]
-    fn create_kitty() {
+    fn create_kitty() -> Result<(), BenchmarkError> {
         let caller: T::AccountId = whitelisted_caller();
+        let destination = T::FeeDestination::get();
+
+        // A caller holding tokens doesn't get a free mint and pays the fee, the heavier path.
+        let ed = T::NativeCurrency::minimum_balance();
+        let fee: BalanceOf<T> = 100u32.into();
+        MintFee::<T>::put(fee);
+        T::NativeCurrency::mint_into(&caller, ed + fee)?;
+        // The fee may be below the existential deposit.
+        T::NativeCurrency::mint_into(&destination, ed)?;
+        let collected = T::NativeCurrency::balance(&destination);
 
         #[extrinsic_call]
         create_kitty(RawOrigin::Signed(caller.clone()));
@@ -23,17 +47,27 @@ mod benchmarks {
         let count = CountForKitties::<T>::get();
         assert_eq!(count, 1);
 
-        let owned = KittiesOwned::<T>::get(caller);
+        let owned = KittiesOwned::<T>::get(&caller);
         assert_eq!(owned.len(), 1);
+        assert!(!FreeMintClaimed::<T>::contains_key(caller));
+        assert_eq!(T::NativeCurrency::balance(&destination), collected + fee);
+
+        Ok(())
     }
 
+    // `f` is the number of kitties owned by the sender, `t` the number owned by the recipient.
     #[benchmark]
-    fn transfer() {
+    fn transfer(
+        f: Linear<1, MAX_KITTIES_OWNED>,
+        t: Linear<0, { MAX_KITTIES_OWNED - 1 }>,
+    ) -> Result<(), BenchmarkError> {
         let caller: T::AccountId = whitelisted_caller();
         let recipient: T::AccountId = account("bob", 0, 0);
 
-        Pallet::<T>::create_kitty(RawOrigin::Signed(caller.clone()).into()).unwrap();
-        let kitty_id = KittiesOwned::<T>::get(caller.clone())[0];
+        mint_kitties::<T>(&caller, 0, f)?;
+        mint_kitties::<T>(&recipient, 1, t)?;
+        // The last kitty of the owner index is the worst case for finding it.
+        let kitty_id = *KittiesOwned::<T>::get(caller.clone()).last().unwrap();
 
         #[extrinsic_call]
         transfer(
@@ -43,11 +77,13 @@ mod benchmarks {
         );
 
         let recipient_owned = KittiesOwned::<T>::get(recipient.clone());
-        assert_eq!(recipient_owned.len(), 1);
-        assert_eq!(recipient_owned[0], kitty_id);
+        assert_eq!(recipient_owned.len() as u32, t + 1);
+        assert!(recipient_owned.contains(&kitty_id));
 
         let caller_owned = KittiesOwned::<T>::get(caller.clone());
-        assert_eq!(caller_owned.len(), 0);
+        assert_eq!(caller_owned.len() as u32, f - 1);
+
+        Ok(())
     }
 
     #[benchmark]
@@ -64,10 +100,15 @@ mod benchmarks {
         assert_eq!(Kitties::<T>::get(kitty_id).unwrap().price, Some(price));
     }
 
+    // `s` is the number of kitties owned by the seller, `b` the number owned by the buyer.
     #[benchmark]
-    fn buy_kitty() -> Result<(), BenchmarkError> {
+    fn buy_kitty(
+        s: Linear<1, MAX_KITTIES_OWNED>,
+        b: Linear<0, { MAX_KITTIES_OWNED - 1 }>,
+    ) -> Result<(), BenchmarkError> {
         let seller: T::AccountId = whitelisted_caller();
         let buyer: T::AccountId = account("bob", 0, 0);
+        let destination = T::FeeDestination::get();
 
         let ed = T::NativeCurrency::minimum_balance();
         let price: BalanceOf<T> = 100u32.into();
@@ -75,9 +116,16 @@ mod benchmarks {
 
         T::NativeCurrency::mint_into(&buyer, balance)?;
         T::NativeCurrency::mint_into(&seller, ed)?;
-
-        Pallet::<T>::create_kitty(RawOrigin::Signed(seller.clone()).into())?;
-        let kitty_id = KittiesOwned::<T>::get(seller.clone())[0];
+        // Paying the seller and the fee destination is the heavier path. The fee may be below the
+        // existential deposit.
+        MarketplaceFee::<T>::put(Permill::from_percent(10));
+        T::NativeCurrency::mint_into(&destination, ed)?;
+        let collected = T::NativeCurrency::balance(&destination);
+
+        mint_kitties::<T>(&seller, 0, s)?;
+        mint_kitties::<T>(&buyer, 1, b)?;
+        // The last kitty of the owner index is the worst case for finding it.
+        let kitty_id = *KittiesOwned::<T>::get(seller.clone()).last().unwrap();
         Pallet::<T>::set_price(
             RawOrigin::Signed(seller.clone()).into(),
             kitty_id,
@@ -90,13 +138,107 @@ mod benchmarks {
         let kitty = Kitties::<T>::get(kitty_id).unwrap();
         assert_eq!(kitty.owner, buyer);
         assert_eq!(kitty.price, None);
+        assert_eq!(KittiesOwned::<T>::get(buyer).len() as u32, b + 1);
+        assert_eq!(
+            T::NativeCurrency::balance(&destination),
+            collected + Permill::from_percent(10) * price
+        );
+
+        Ok(())
+    }
+
+    #[benchmark]
+    fn force_transfer() -> Result<(), BenchmarkError> {
+        let origin =
+            T::ForceOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+        let owner: T::AccountId = whitelisted_caller();
+        let recipient: T::AccountId = account("bob", 0, 0);
+
+        Pallet::<T>::create_kitty(RawOrigin::Signed(owner.clone()).into())?;
+        let kitty_id = KittiesOwned::<T>::get(owner.clone())[0];
+
+        #[extrinsic_call]
+        _(origin as T::RuntimeOrigin, recipient.clone(), kitty_id);
+
+        assert_eq!(Kitties::<T>::get(kitty_id).unwrap().owner, recipient);
+        assert_eq!(KittiesOwned::<T>::get(owner).len(), 0);
+
+        Ok(())
+    }
+
+    #[benchmark]
+    fn force_burn() -> Result<(), BenchmarkError> {
+        let origin =
+            T::ForceOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+        let owner: T::AccountId = whitelisted_caller();
+
+        Pallet::<T>::create_kitty(RawOrigin::Signed(owner.clone()).into())?;
+        let kitty_id = KittiesOwned::<T>::get(owner.clone())[0];
+
+        #[extrinsic_call]
+        _(origin as T::RuntimeOrigin, kitty_id);
+
+        assert!(!Kitties::<T>::contains_key(kitty_id));
+        assert_eq!(CountForKitties::<T>::get(), 0);
+
+        Ok(())
+    }
+
+    #[benchmark]
+    fn force_set_price() -> Result<(), BenchmarkError> {
+        let origin =
+            T::ForceOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+        let owner: T::AccountId = whitelisted_caller();
+        let price: BalanceOf<T> = 100u32.into();
+
+        Pallet::<T>::create_kitty(RawOrigin::Signed(owner.clone()).into())?;
+        let kitty_id = KittiesOwned::<T>::get(owner)[0];
+
+        #[extrinsic_call]
+        _(origin as T::RuntimeOrigin, kitty_id, Some(price));
+
+        assert_eq!(Kitties::<T>::get(kitty_id).unwrap().price, Some(price));
+
+        Ok(())
+    }
+
+    #[benchmark]
+    fn set_paused() -> Result<(), BenchmarkError> {
+        let origin =
+            T::PauseOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+        let scope = PauseScope {
+            minting: true,
+            transfers: true,
+            sales: true,
+        };
+
+        #[extrinsic_call]
+        _(origin as T::RuntimeOrigin, scope);
+
+        assert_eq!(Paused::<T>::get(), scope);
+
+        Ok(())
+    }
+
+    #[benchmark]
+    fn set_fees() -> Result<(), BenchmarkError> {
+        let origin =
+            T::ForceOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+        let mint_fee: BalanceOf<T> = 100u32.into();
+        let marketplace_fee = Permill::from_percent(5);
+
+        #[extrinsic_call]
+        _(origin as T::RuntimeOrigin, mint_fee, marketplace_fee);
+
+        assert_eq!(MintFee::<T>::get(), mint_fee);
+        assert_eq!(MarketplaceFee::<T>::get(), marketplace_fee);
 
         Ok(())
     }
 
     impl_benchmark_test_suite!(
-        Template,
-        crate::tests::new_test_ext(),
-        crate::tests::TestRuntime
+        Collectables,
+        crate::mock::new_test_ext(),
+        crate::mock::TestRuntime
     );
 }
@@ -0,0 +1,52 @@
+//! Generation of the DNA of newly minted kitties.
+
+use codec::Encode;
+use frame_system::pallet_prelude::BlockNumberFor;
+use sp_io::hashing::blake2_256;
+
+/// What is known about a mint at the time its DNA is generated.
+pub struct MintContext<T: frame_system::Config> {
+    pub parent_hash: T::Hash,
+    pub block_number: BlockNumberFor<T>,
+    /// Index of the extrinsic doing the mint, `None` outside of extrinsics (e.g. in tests).
+    pub extrinsic_index: Option<u32>,
+    /// Number of kitties in existence before this one.
+    pub kitty_count: u32,
+}
+
+/// Decides the DNA of new kitties.
+///
+/// The DNA doubles as the kitty id, so generators should make collisions unlikely. A collision
+/// doesn't corrupt anything, the mint just fails with `DuplicatedKitty`.
+pub trait DnaGenerator<T: frame_system::Config> {
+    fn generate_dna(minter: &T::AccountId, context: &MintContext<T>) -> [u8; 32];
+}
+
+/// Hashes the mint context into a DNA that is unpredictable before the block is built.
+///
+/// The minter is deliberately left out so that existing chains keep producing the same DNA.
+pub struct HashedDna;
+
+impl<T: frame_system::Config> DnaGenerator<T> for HashedDna {
+    fn generate_dna(_minter: &T::AccountId, context: &MintContext<T>) -> [u8; 32] {
+        let unique_payload = (
+            context.parent_hash,
+            context.block_number,
+            context.extrinsic_index,
+            context.kitty_count,
+        );
+        blake2_256(&unique_payload.encode())
+    }
+}
+
+/// Derives the DNA from the kitty count alone, so the n-th kitty always gets the same DNA.
+///
+/// Meant for tests. Burning kitties lowers the count, after which this hands out DNA that may
+/// still be in use.
+pub struct SequentialDna;
+
+impl<T: frame_system::Config> DnaGenerator<T> for SequentialDna {
+    fn generate_dna(_minter: &T::AccountId, context: &MintContext<T>) -> [u8; 32] {
+        blake2_256(&context.kitty_count.encode())
+    }
+}
@@ -0,0 +1,274 @@
+use super::*;
+use codec::{Decode, Encode};
+use core::marker::PhantomData;
+use frame_support::traits::{Get, IsSubType};
+use scale_info::TypeInfo;
+use sp_runtime::{
+    traits::{DispatchInfoOf, PostDispatchInfoOf, SignedExtension},
+    transaction_validity::{
+        InvalidTransaction, TransactionLongevity, TransactionValidity, TransactionValidityError,
+        ValidTransaction,
+    },
+    DispatchResult,
+};
+
+/// Codes carried by `InvalidTransaction::Custom` when [`CheckKittyPurchase`] rejects a call.
+#[repr(u8)]
+pub enum PurchaseValidity {
+    /// The kitty does not exist.
+    NoKitty = 0,
+    /// The kitty is not listed for sale.
+    NotForSale = 1,
+    /// The buyer's `max_price` is below the listed price.
+    MaxPriceTooLow = 2,
+    /// Sales are paused.
+    SalesPaused = 3,
+}
+
+impl From<PurchaseValidity> for TransactionValidityError {
+    fn from(code: PurchaseValidity) -> Self {
+        InvalidTransaction::Custom(code as u8).into()
+    }
+}
+
+/// Rejects `buy_kitty` transactions that would certainly fail, so they never enter the pool or a
+/// block and the buyer doesn't pay fees for them.
+///
+/// A purchase is doomed if the kitty does not exist, is not listed, is listed above the buyer's
+/// `max_price`, or if sales are paused. Every other call passes through untouched, including
+/// purchases wrapped in another call such as a utility batch; those fail at dispatch as usual.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, TypeInfo)]
+#[scale_info(skip_type_params(T))]
+pub struct CheckKittyPurchase<T: Config + Send + Sync>(PhantomData<T>);
+
+impl<T: Config + Send + Sync> CheckKittyPurchase<T> {
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T: Config + Send + Sync> Default for CheckKittyPurchase<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Config + Send + Sync> core::fmt::Debug for CheckKittyPurchase<T> {
+    #[cfg(feature = "std")]
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "CheckKittyPurchase")
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn fmt(&self, _: &mut core::fmt::Formatter) -> core::fmt::Result {
+        Ok(())
+    }
+}
+
+impl<T: Config> Pallet<T> {
+    /// Checks, without touching storage, whether a purchase of `kitty_id` for at most
+    /// `max_price` can succeed.
+    pub fn validate_purchase(
+        kitty_id: &[u8; 32],
+        max_price: BalanceOf<T>,
+    ) -> Result<(), PurchaseValidity> {
+        if Paused::<T>::get().sales {
+            return Err(PurchaseValidity::SalesPaused);
+        }
+        let kitty = Kitties::<T>::get(kitty_id).ok_or(PurchaseValidity::NoKitty)?;
+        match kitty.price {
+            Some(price) if price > max_price => Err(PurchaseValidity::MaxPriceTooLow),
+            Some(_) => Ok(()),
+            None => Err(PurchaseValidity::NotForSale),
+        }
+    }
+}
+
+impl<T: Config + Send + Sync> SignedExtension for CheckKittyPurchase<T>
+where
+    T::RuntimeCall: IsSubType<Call<T>>,
+{
+    const IDENTIFIER: &'static str = "CheckKittyPurchase";
+    type AccountId = T::AccountId;
+    type Call = T::RuntimeCall;
+    type AdditionalSigned = ();
+    type Pre = ();
+
+    fn additional_signed(&self) -> Result<(), TransactionValidityError> {
+        Ok(())
+    }
+
+    fn validate(
+        &self,
+        _who: &Self::AccountId,
+        call: &Self::Call,
+        _info: &DispatchInfoOf<Self::Call>,
+        _len: usize,
+    ) -> TransactionValidity {
+        if let Some(Call::buy_kitty {
+            kitty_id,
+            max_price,
+        }) = call.is_sub_type()
+        {
+            Pallet::<T>::validate_purchase(kitty_id, *max_price)?;
+        }
+        Ok(ValidTransaction::default())
+    }
+
+    fn pre_dispatch(
+        self,
+        who: &Self::AccountId,
+        call: &Self::Call,
+        info: &DispatchInfoOf<Self::Call>,
+        len: usize,
+    ) -> Result<(), TransactionValidityError> {
+        self.validate(who, call, info, len).map(|_| ())
+    }
+}
+
+/// Lets brand-new accounts submit their free first mint.
+///
+/// `frame_system::CheckNonce` rejects transactions from accounts that don't exist, and an account
+/// without native tokens doesn't. For a `create_kitty` that qualifies as a free mint (see
+/// [`Pallet::can_mint_for_free`]) this gives the sender a sufficient reference first, so it must
+/// come before `CheckNonce` in the runtime's `SignedExtra`. The reference is kept whether or not
+/// the kitty is minted: releasing it after a failed mint would reap the account and reset its
+/// nonce, making the same transaction valid again. A failed mint uses up the free mint instead.
+///
+/// Free mints cost their sender nothing, so they must not crowd out paying transactions: they
+/// get no priority, expire from the pool after [`FREE_MINT_LONGEVITY`] blocks and are left out
+/// of a block once its free mints are used up. Nor may they pile up in the pool: each takes one
+/// of `MaxFreeMintsPerBlock` pool slots, picked by its sender, and an account can only have one
+/// in the pool whatever nonce it signs it with. Mints whose slot is taken are rejected until the
+/// mint holding it is included or expires.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, TypeInfo)]
+#[scale_info(skip_type_params(T))]
+pub struct CheckFreeMint<T: Config + Send + Sync>(PhantomData<T>);
+
+impl<T: Config + Send + Sync> CheckFreeMint<T> {
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T: Config + Send + Sync> Default for CheckFreeMint<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Config + Send + Sync> core::fmt::Debug for CheckFreeMint<T> {
+    #[cfg(feature = "std")]
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "CheckFreeMint")
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn fmt(&self, _: &mut core::fmt::Formatter) -> core::fmt::Result {
+        Ok(())
+    }
+}
+
+/// How many blocks a free mint stays in the transaction pool.
+pub const FREE_MINT_LONGEVITY: TransactionLongevity = 8;
+
+impl<T: Config + Send + Sync> CheckFreeMint<T>
+where
+    T::RuntimeCall: IsSubType<Call<T>>,
+{
+    /// Whether `call` is a `create_kitty` by an account that doesn't exist yet, which can only
+    /// be a free mint.
+    fn is_new_account_mint(who: &T::AccountId, call: &T::RuntimeCall) -> bool {
+        matches!(call.is_sub_type(), Some(Call::create_kitty {}))
+            && frame_system::Pallet::<T>::providers(who) == 0
+            && frame_system::Pallet::<T>::sufficients(who) == 0
+    }
+
+    /// The pool slot of a free mint by `who`, one of `MaxFreeMintsPerBlock`.
+    fn pool_slot(who: &T::AccountId) -> u32 {
+        let hash = sp_io::hashing::blake2_256(&who.encode());
+        u32::from_le_bytes([hash[0], hash[1], hash[2], hash[3]])
+            % T::MaxFreeMintsPerBlock::get().max(1)
+    }
+
+    /// Gives `who` a sufficient reference for its free mint, returns whether it did.
+    ///
+    /// Fails with `ExhaustsResources` once the free mints of the block are used up, so that the
+    /// mint waits for a later block.
+    fn provide_account(
+        who: &T::AccountId,
+        call: &T::RuntimeCall,
+    ) -> Result<bool, TransactionValidityError> {
+        if !Self::is_new_account_mint(who, call) {
+            return Ok(false);
+        }
+        if Pallet::<T>::free_mints_in_block() >= T::MaxFreeMintsPerBlock::get() {
+            return Err(InvalidTransaction::ExhaustsResources.into());
+        }
+        if !Pallet::<T>::can_mint_for_free(who) {
+            return Ok(false);
+        }
+        frame_system::Pallet::<T>::inc_sufficients(who);
+        Ok(true)
+    }
+}
+
+impl<T: Config + Send + Sync> SignedExtension for CheckFreeMint<T>
+where
+    T::RuntimeCall: IsSubType<Call<T>>,
+{
+    const IDENTIFIER: &'static str = "CheckFreeMint";
+    type AccountId = T::AccountId;
+    type Call = T::RuntimeCall;
+    type AdditionalSigned = ();
+    /// The account given a sufficient reference, if any.
+    type Pre = Option<T::AccountId>;
+
+    fn additional_signed(&self) -> Result<(), TransactionValidityError> {
+        Ok(())
+    }
+
+    fn validate(
+        &self,
+        who: &Self::AccountId,
+        call: &Self::Call,
+        _info: &DispatchInfoOf<Self::Call>,
+        _len: usize,
+    ) -> TransactionValidity {
+        // Pool validation runs on a throwaway state, the reference only lives long enough for
+        // `CheckNonce` to see it.
+        if !Self::provide_account(who, call)? {
+            return Ok(ValidTransaction::default());
+        }
+        // Free mints skip `ChargeTransactionPayment`, so they get no priority from it either.
+        ValidTransaction::with_tag_prefix(Self::IDENTIFIER)
+            .priority(0)
+            .longevity(FREE_MINT_LONGEVITY)
+            .and_provides((b"account", who))
+            .and_provides((b"slot", Self::pool_slot(who)))
+            .build()
+    }
+
+    fn pre_dispatch(
+        self,
+        who: &Self::AccountId,
+        call: &Self::Call,
+        _info: &DispatchInfoOf<Self::Call>,
+        _len: usize,
+    ) -> Result<Self::Pre, TransactionValidityError> {
+        Ok(Self::provide_account(who, call)?.then(|| who.clone()))
+    }
+
+    fn post_dispatch(
+        pre: Option<Self::Pre>,
+        _info: &DispatchInfoOf<Self::Call>,
+        _post_info: &PostDispatchInfoOf<Self::Call>,
+        _len: usize,
+        result: &DispatchResult,
+    ) -> Result<(), TransactionValidityError> {
+        if let (Some(Some(who)), Err(_)) = (pre, result) {
+            Pallet::<T>::claim_free_mint(&who);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+//! Hooks through which other pallets are notified about kitty movements.
+//!
+//! The hooks run synchronously as part of the call that caused them, after all storage changes of
+//! the kitties pallet have been made. Multiple handlers can be combined with a tuple.
+//!
+//! Every hook declares the most it can consume with `weight`, which the kitty calls add to their
+//! own weight. As that is a fixed bound, a hook must do O(1) work, e.g. never iterate over storage
+//! that users can grow. Pallets moving kitties through the nonfungible traits run the hooks too,
+//! and have to account for their weight themselves.
+
+use frame_support::weights::Weight;
+
+/// Called after a new kitty has been minted.
+pub trait OnKittyCreated<AccountId> {
+    /// The most [`Self::on_kitty_created`] can consume.
+    fn weight() -> Weight;
+
+    fn on_kitty_created(owner: &AccountId, kitty_id: &[u8; 32]);
+}
+
+#[impl_trait_for_tuples::impl_for_tuples(30)]
+impl<AccountId> OnKittyCreated<AccountId> for Tuple {
+    fn weight() -> Weight {
+        let mut weight = Weight::zero();
+        for_tuples!( #( weight = weight.saturating_add(Tuple::weight()); )* );
+        weight
+    }
+
+    fn on_kitty_created(owner: &AccountId, kitty_id: &[u8; 32]) {
+        for_tuples!( #( Tuple::on_kitty_created(owner, kitty_id); )* );
+    }
+}
+
+/// Called after a kitty changed owner, including transfers made as part of a sale.
+pub trait OnKittyTransferred<AccountId> {
+    /// The most [`Self::on_kitty_transferred`] can consume.
+    fn weight() -> Weight;
+
+    fn on_kitty_transferred(from: &AccountId, to: &AccountId, kitty_id: &[u8; 32]);
+}
+
+#[impl_trait_for_tuples::impl_for_tuples(30)]
+impl<AccountId> OnKittyTransferred<AccountId> for Tuple {
+    fn weight() -> Weight {
+        let mut weight = Weight::zero();
+        for_tuples!( #( weight = weight.saturating_add(Tuple::weight()); )* );
+        weight
+    }
+
+    fn on_kitty_transferred(from: &AccountId, to: &AccountId, kitty_id: &[u8; 32]) {
+        for_tuples!( #( Tuple::on_kitty_transferred(from, to, kitty_id); )* );
+    }
+}
+
+/// Called after a kitty has been bought, once the payment and the transfer went through.
+pub trait OnKittySold<AccountId, Balance> {
+    /// The most [`Self::on_kitty_sold`] can consume.
+    fn weight() -> Weight;
+
+    fn on_kitty_sold(seller: &AccountId, buyer: &AccountId, kitty_id: &[u8; 32], price: Balance);
+}
+
+#[impl_trait_for_tuples::impl_for_tuples(30)]
+impl<AccountId, Balance: Clone> OnKittySold<AccountId, Balance> for Tuple {
+    fn weight() -> Weight {
+        let mut weight = Weight::zero();
+        for_tuples!( #( weight = weight.saturating_add(Tuple::weight()); )* );
+        weight
+    }
+
+    fn on_kitty_sold(seller: &AccountId, buyer: &AccountId, kitty_id: &[u8; 32], price: Balance) {
+        for_tuples!( #( Tuple::on_kitty_sold(seller, buyer, kitty_id, price.clone()); )* );
+    }
+}
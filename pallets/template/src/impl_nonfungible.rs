@@ -0,0 +1,59 @@
+//! Implementations of the single-collection `nonfungible_v2` traits, so that other pallets can
+//! inspect, move, mint and burn kitties without depending on this pallet directly.
+
+use super::*;
+use frame_support::{
+    pallet_prelude::*,
+    traits::tokens::nonfungible_v2::{Inspect, Mutate, Transfer},
+};
+
+impl<T: Config> Inspect<T::AccountId> for Pallet<T> {
+    type ItemId = [u8; 32];
+
+    fn owner(item: &Self::ItemId) -> Option<T::AccountId> {
+        Kitties::<T>::get(item).map(|kitty| kitty.owner)
+    }
+
+    fn can_transfer(item: &Self::ItemId) -> bool {
+        Kitties::<T>::contains_key(item) && !Paused::<T>::get().transfers
+    }
+}
+
+impl<T: Config> Transfer<T::AccountId> for Pallet<T> {
+    /// Moves the kitty and removes any listing, so that nobody can buy it out of the hands of
+    /// e.g. an escrow it was moved into.
+    fn transfer(item: &Self::ItemId, destination: &T::AccountId) -> DispatchResult {
+        ensure!(!Paused::<T>::get().transfers, Error::<T>::Paused);
+        let kitty = Kitties::<T>::get(item).ok_or(Error::<T>::NoKitty)?;
+        Self::do_transfer(kitty.owner, destination.clone(), *item)?;
+        if kitty.price.is_some() {
+            Self::do_set_price(destination.clone(), *item, None)?;
+        }
+        Ok(())
+    }
+
+    /// Kitties can't be locked individually, only through the marketplace pause switch.
+    fn disable_transfer(_item: &Self::ItemId) -> DispatchResult {
+        Err(sp_runtime::TokenError::Unsupported.into())
+    }
+
+    fn enable_transfer(_item: &Self::ItemId) -> DispatchResult {
+        Err(sp_runtime::TokenError::Unsupported.into())
+    }
+}
+
+impl<T: Config> Mutate<T::AccountId, ()> for Pallet<T> {
+    fn mint_into(
+        item: &Self::ItemId,
+        who: &T::AccountId,
+        _config: &(),
+        _deposit_collection_owner: bool,
+    ) -> DispatchResult {
+        ensure!(!Paused::<T>::get().minting, Error::<T>::Paused);
+        Self::mint(who.clone(), *item)
+    }
+
+    fn burn(item: &Self::ItemId, maybe_check_owner: Option<&T::AccountId>) -> DispatchResult {
+        Self::do_burn(*item, maybe_check_owner)
+    }
+}
@@ -1,20 +1,19 @@
 use super::*;
-use codec::Encode;
+use alloc::vec::Vec;
 use frame_support::pallet_prelude::*;
-use frame_support::traits::fungible::Mutate;
-use frame_support::traits::tokens::Preservation;
-use sp_io::hashing::blake2_256;
+use frame_support::traits::fungible::{Inspect, Mutate};
+use frame_support::traits::tokens::{Fortitude, Precision, Preservation};
+use sp_runtime::traits::{Saturating, Zero};
 
 impl<T: Config> Pallet<T> {
-    pub fn gen_dna() -> [u8; 32] {
-        let unique_payload = (
-            frame_system::Pallet::<T>::parent_hash(),
-            frame_system::Pallet::<T>::block_number(),
-            frame_system::Pallet::<T>::extrinsic_index(),
-            CountForKitties::<T>::get(),
-        );
-        let serialized_payload = unique_payload.encode();
-        blake2_256(&serialized_payload)
+    pub fn gen_dna(minter: &T::AccountId) -> [u8; 32] {
+        let context = MintContext::<T> {
+            parent_hash: frame_system::Pallet::<T>::parent_hash(),
+            block_number: frame_system::Pallet::<T>::block_number(),
+            extrinsic_index: frame_system::Pallet::<T>::extrinsic_index(),
+            kitty_count: CountForKitties::<T>::get(),
+        };
+        T::DnaGenerator::generate_dna(minter, &context)
     }
 
     pub fn do_transfer(from: T::AccountId, to: T::AccountId, dna: [u8; 32]) -> DispatchResult {
@@ -25,7 +24,7 @@ impl<T: Config> Pallet<T> {
 
         // Kitty exists
         // ensure!(Kitties::<T>::contains_key(dna), Error::<T>::NoKitty);
-        let mut kitty = match Kitties::<T>::get(dna) {
+        let kitty = match Kitties::<T>::get(dna) {
             Some(kitty) => kitty,
             None => return Err(Error::<T>::NoKitty.into()),
         };
@@ -38,6 +37,24 @@ impl<T: Config> Pallet<T> {
         // (Workshop implementation)
         // Could be checked using == sign
 
+        Self::move_kitty(kitty, &to)?;
+
+        Self::deposit_event(Event::<T>::Transferred {
+            from,
+            to,
+            kitty_id: dna,
+        });
+        Ok(())
+    }
+
+    /// Hands `kitty` over to `to`, keeping both owners' `KittiesOwned` entries in sync.
+    ///
+    /// Performs no permission checks; callers must make sure the move is allowed. Notifies
+    /// [`Config::OnKittyTransferred`].
+    fn move_kitty(mut kitty: Kitty<T>, to: &T::AccountId) -> DispatchResult {
+        let dna = kitty.dna;
+        let from = kitty.owner.clone();
+
         // (Workshop implementation)
         // kitty.owner = to.clone();
         // Kitty.owner assigned here Doesn't seem like it matters
@@ -45,7 +62,7 @@ impl<T: Config> Pallet<T> {
         // Add kitty to owned map
         // let mut to_owned = KittiesOwned::<T>::get(to.clone());
         // (Workshop implementation)
-        let mut to_owned = KittiesOwned::<T>::get(&to);
+        let mut to_owned = KittiesOwned::<T>::get(to);
         // conclusion can borrow the to instead of cloning it. Better. Adopt.
         // TODO: Review borrow content
 
@@ -86,14 +103,10 @@ impl<T: Config> Pallet<T> {
         kitty.owner = to.clone();
         // kitty.price = None;
         Kitties::<T>::insert(dna, kitty);
-        KittiesOwned::<T>::insert(&to, to_owned);
+        KittiesOwned::<T>::insert(to, to_owned);
         KittiesOwned::<T>::insert(&from, from_owned);
 
-        Self::deposit_event(Event::<T>::Transferred {
-            from,
-            to,
-            kitty_id: dna,
-        });
+        T::OnKittyTransferred::on_kitty_transferred(&from, to, &dna);
         Ok(())
     }
 
@@ -126,6 +139,8 @@ impl<T: Config> Pallet<T> {
         Kitties::<T>::insert(dna, kitty);
         KittiesOwned::<T>::try_append(&owner, dna).map_err(|_| Error::<T>::TooManyOwned)?;
 
+        T::OnKittyCreated::on_kitty_created(&owner, &dna);
+
         Self::deposit_event(Event::<T>::Created {
             owner,
             kitty_id: dna,
@@ -173,16 +188,28 @@ impl<T: Config> Pallet<T> {
             None => return Err(Error::<T>::NotForSale.into()),
         };
 
-        T::NativeCurrency::transfer(&buyer, &kitty.owner, price, Preservation::Preserve)?;
+        // The marketplace keeps its cut, the seller gets the rest.
+        let fee = MarketplaceFee::<T>::get().mul_floor(price);
+        T::NativeCurrency::transfer(
+            &buyer,
+            &kitty.owner,
+            price.saturating_sub(fee),
+            Preservation::Preserve,
+        )?;
+        Self::pay_fee(&buyer, fee)?;
 
         // maybe refactor to accept &mut buyer? ownership move cause `buyer_address`
+        let seller = kitty.owner.clone();
         Self::do_transfer(kitty.owner, buyer.clone(), kitty_id)?;
 
         // Call set price to remove the price.
         // Q: Worth to use this method instead of directly setting the price to None?
         Self::do_set_price(buyer, kitty_id, None)?;
 
+        T::OnKittySold::on_kitty_sold(&seller, &buyer_address, &kitty_id, price);
+
         Self::deposit_event(Event::<T>::Sold {
+            seller,
             buyer: buyer_address,
             kitty_id,
             price,
@@ -190,4 +217,202 @@ impl<T: Config> Pallet<T> {
 
         return Ok(());
     }
+
+    pub fn do_force_transfer(to: T::AccountId, kitty_id: [u8; 32]) -> DispatchResult {
+        let mut kitty = Kitties::<T>::get(kitty_id).ok_or(Error::<T>::NoKitty)?;
+        let from = kitty.owner.clone();
+        ensure!(from != to, Error::<T>::TransferToSelf);
+
+        // The listing was made by the previous owner, so it must not carry over.
+        kitty.price = None;
+        Self::move_kitty(kitty, &to)?;
+
+        Self::deposit_event(Event::<T>::ForceTransferred { from, to, kitty_id });
+        Ok(())
+    }
+
+    pub fn do_force_burn(kitty_id: [u8; 32]) -> DispatchResult {
+        let kitty = Self::remove_kitty(kitty_id)?;
+
+        Self::deposit_event(Event::<T>::ForceBurned {
+            owner: kitty.owner,
+            kitty_id,
+        });
+        Ok(())
+    }
+
+    pub fn do_burn(kitty_id: [u8; 32], maybe_check_owner: Option<&T::AccountId>) -> DispatchResult {
+        let kitty = Kitties::<T>::get(kitty_id).ok_or(Error::<T>::NoKitty)?;
+        if let Some(owner) = maybe_check_owner {
+            ensure!(kitty.owner == *owner, Error::<T>::NotOwner);
+        }
+        let kitty = Self::remove_kitty(kitty_id)?;
+
+        Self::deposit_event(Event::<T>::Burned {
+            owner: kitty.owner,
+            kitty_id,
+        });
+        Ok(())
+    }
+
+    /// Deletes a kitty together with its owner index entry and returns it.
+    fn remove_kitty(kitty_id: [u8; 32]) -> Result<Kitty<T>, DispatchError> {
+        let kitty = Kitties::<T>::take(kitty_id).ok_or(Error::<T>::NoKitty)?;
+
+        KittiesOwned::<T>::mutate(&kitty.owner, |owned| owned.retain(|dna| *dna != kitty_id));
+        CountForKitties::<T>::mutate(|count| *count = count.saturating_sub(1));
+
+        Ok(kitty)
+    }
+
+    pub fn do_force_set_price(kitty_id: [u8; 32], price: Option<BalanceOf<T>>) -> DispatchResult {
+        let mut kitty = Kitties::<T>::get(kitty_id).ok_or(Error::<T>::NoKitty)?;
+        let owner = kitty.owner.clone();
+
+        kitty.price = price;
+        Kitties::<T>::insert(kitty_id, kitty);
+
+        Self::deposit_event(Event::<T>::ForcePriceSet {
+            owner,
+            kitty_id,
+            new_price: price,
+        });
+        Ok(())
+    }
+
+    /// The kitty with the given DNA, if any.
+    pub fn kitty(dna: [u8; 32]) -> Option<Kitty<T>> {
+        Kitties::<T>::get(dna)
+    }
+
+    /// All kitties owned by `owner`, in the order of their owner index.
+    pub fn kitties_of(owner: &T::AccountId) -> Vec<Kitty<T>> {
+        KittiesOwned::<T>::get(owner)
+            .iter()
+            .filter_map(Kitties::<T>::get)
+            .collect()
+    }
+
+    /// The number of kitties owned by `who`, without decoding the whole owner index.
+    pub fn owned_count(who: &T::AccountId) -> u32 {
+        KittiesOwned::<T>::decode_len(who).unwrap_or_default() as u32
+    }
+
+    pub fn kitty_count() -> u32 {
+        CountForKitties::<T>::get()
+    }
+
+    /// Up to `limit` kitties with a price set, continuing after the kitty `start` if given.
+    ///
+    /// Pages follow the storage order of `Kitties`, so `start` should be the DNA of the last
+    /// kitty returned by the previous call.
+    pub fn listed_kitties(start: Option<[u8; 32]>, limit: u32) -> Vec<Kitty<T>> {
+        let kitties = match start {
+            Some(dna) => Kitties::<T>::iter_from(Kitties::<T>::hashed_key_for(dna)),
+            None => Kitties::<T>::iter(),
+        };
+        kitties
+            .map(|(_, kitty)| kitty)
+            .filter(|kitty| kitty.price.is_some())
+            .take(limit as usize)
+            .collect()
+    }
+
+    /// Whether `who` may mint a kitty without paying fees.
+    ///
+    /// Only brand-new players qualify: accounts that hold no native tokens, own no kitties and
+    /// never claimed a free mint before. At most `MaxFreeMintsPerBlock` free mints fit in a block;
+    /// the rest have to wait for the next one.
+    pub fn can_mint_for_free(who: &T::AccountId) -> bool {
+        !Paused::<T>::get().minting
+            && Self::free_mints_in_block() < T::MaxFreeMintsPerBlock::get()
+            && !FreeMintClaimed::<T>::contains_key(who)
+            && Self::owned_count(who) == 0
+            && T::NativeCurrency::total_balance(who).is_zero()
+    }
+
+    /// Transfers `fee` from `who` to `T::FeeDestination`. Zero fees are skipped, so no fee
+    /// destination has to exist while fees are disabled.
+    ///
+    /// A fee below the existential deposit can't create the fee destination, so while it doesn't
+    /// exist such a fee is burnt instead.
+    pub(crate) fn pay_fee(who: &T::AccountId, fee: BalanceOf<T>) -> DispatchResult {
+        if fee.is_zero() {
+            return Ok(());
+        }
+        let destination = T::FeeDestination::get();
+        if fee < T::NativeCurrency::minimum_balance()
+            && T::NativeCurrency::total_balance(&destination).is_zero()
+        {
+            T::NativeCurrency::burn_from(
+                who,
+                fee,
+                Preservation::Preserve,
+                Precision::Exact,
+                Fortitude::Polite,
+            )?;
+        } else {
+            T::NativeCurrency::transfer(who, &destination, fee, Preservation::Preserve)?;
+        }
+        Ok(())
+    }
+
+    /// Number of free mints in the current block.
+    pub fn free_mints_in_block() -> u32 {
+        let (block, count) = FreeMintsInBlock::<T>::get();
+        if block == frame_system::Pallet::<T>::block_number() {
+            count
+        } else {
+            0
+        }
+    }
+
+    /// Records that `who` used their free mint.
+    pub(crate) fn claim_free_mint(who: &T::AccountId) {
+        let now = frame_system::Pallet::<T>::block_number();
+        FreeMintClaimed::<T>::insert(who, now);
+        FreeMintsInBlock::<T>::put((now, Self::free_mints_in_block().saturating_add(1)));
+    }
+
+    /// Checks the invariants of the pallet's storage:
+    ///
+    /// - `CountForKitties` equals the number of entries in `Kitties`.
+    /// - Every kitty appears exactly once in the `KittiesOwned` entry of its owner.
+    /// - Every `KittiesOwned` entry only lists existing kitties owned by that account.
+    #[cfg(any(feature = "try-runtime", test))]
+    pub fn do_try_state() -> Result<(), sp_runtime::TryRuntimeError> {
+        let mut kitties = 0u32;
+        for (dna, kitty) in Kitties::<T>::iter() {
+            kitties += 1;
+            ensure!(
+                kitty.dna == dna,
+                "Kitty is stored under a different DNA than its own"
+            );
+            let listed = KittiesOwned::<T>::get(&kitty.owner)
+                .iter()
+                .filter(|owned| **owned == dna)
+                .count();
+            ensure!(
+                listed == 1,
+                "Kitty is not listed exactly once in its owner's KittiesOwned"
+            );
+        }
+        ensure!(
+            CountForKitties::<T>::get() == kitties,
+            "CountForKitties does not match the number of kitties"
+        );
+
+        for (owner, owned) in KittiesOwned::<T>::iter() {
+            for dna in owned {
+                let kitty = Kitties::<T>::get(dna)
+                    .ok_or("KittiesOwned lists a kitty that does not exist")?;
+                ensure!(
+                    kitty.owner == owner,
+                    "KittiesOwned lists a kitty owned by another account"
+                );
+            }
+        }
+
+        Ok(())
+    }
 }
@@ -1,8 +1,11 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+extern crate alloc;
+
 // This module contains the unit tests for this pallet.
 // Learn about pallet unit testing here: https://docs.substrate.io/test/unit-testing/
 // #[cfg(test)]
+mod impl_nonfungible;
 mod impls;
 
 // FRAME pallets require their own "mock runtimes" to be able to run unit tests. This module
@@ -20,7 +23,13 @@ pub use pallet::*;
 // for each dispatchable and generates this pallet's weight.rs file. Learn more about benchmarking here: https://docs.substrate.io/test/benchmark/
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
+pub mod dna;
+pub mod extensions;
+pub mod hooks;
 pub mod weights;
+pub use dna::*;
+pub use extensions::*;
+pub use hooks::*;
 pub use weights::*;
 
 // All pallet logic is defined in its own module and must be annotated by the `pallet` attribute.
@@ -28,16 +37,28 @@ pub use weights::*;
 pub mod pallet {
     // Import various useful types required by all FRAME pallets.
     use super::*;
+    use alloc::vec::Vec;
     use frame_support::{
         pallet_prelude::*,
         traits::fungible::{Inspect, Mutate},
         Blake2_128Concat,
     };
     use frame_system::pallet_prelude::*;
+    use sp_runtime::Permill;
+
+    /// The most kitties a single account can own.
+    pub const MAX_KITTIES_OWNED: u32 = 100;
 
     pub type BalanceOf<T> =
         <<T as Config>::NativeCurrency as Inspect<<T as frame_system::Config>::AccountId>>::Balance;
 
+    /// A kitty minted at genesis, as `(owner, dna, price)`.
+    pub type GenesisKitty<T> = (
+        <T as frame_system::Config>::AccountId,
+        [u8; 32],
+        Option<BalanceOf<T>>,
+    );
+
     // The `Pallet` struct serves as a placeholder to implement traits, methods and dispatchables
     // (`Call`s) in this pallet.
     #[pallet::pallet]
@@ -55,6 +76,23 @@ pub mod pallet {
         type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
         type WeightInfo: WeightInfo;
         type NativeCurrency: Inspect<Self::AccountId> + Mutate<Self::AccountId>;
+        /// Origin allowed to move, burn and reprice any kitty, e.g. to moderate the marketplace.
+        type ForceOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+        /// Origin allowed to halt and resume parts of the marketplace.
+        type PauseOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+        /// Handler notified whenever a kitty is minted.
+        type OnKittyCreated: OnKittyCreated<Self::AccountId>;
+        /// Handler notified whenever a kitty changes owner.
+        type OnKittyTransferred: OnKittyTransferred<Self::AccountId>;
+        /// Handler notified whenever a kitty is bought.
+        type OnKittySold: OnKittySold<Self::AccountId, BalanceOf<Self>>;
+        /// Decides the DNA of kitties created with `create_kitty`.
+        type DnaGenerator: DnaGenerator<Self>;
+        /// How many accounts may mint their first kitty for free in a single block.
+        #[pallet::constant]
+        type MaxFreeMintsPerBlock: Get<u32>;
+        /// Account collecting the mint and marketplace fees, e.g. the treasury.
+        type FeeDestination: Get<Self::AccountId>;
     }
 
     #[derive(Encode, Decode, TypeInfo, MaxEncodedLen)]
@@ -64,6 +102,19 @@ pub mod pallet {
         pub owner: T::AccountId,
         pub price: Option<BalanceOf<T>>,
     }
+
+    /// The parts of the marketplace that are currently halted.
+    #[derive(
+        Clone, Copy, Default, PartialEq, Eq, Encode, Decode, TypeInfo, MaxEncodedLen, RuntimeDebug,
+    )]
+    pub struct PauseScope {
+        /// `create_kitty` is disabled.
+        pub minting: bool,
+        /// `transfer` is disabled.
+        pub transfers: bool,
+        /// Listing kitties with `set_price` and buying them with `buy_kitty` is disabled.
+        pub sales: bool,
+    }
     /// A storage item for this pallet.
     ///
     /// In this template, we are declaring a storage item called `Something` that stores a single
@@ -77,15 +128,77 @@ pub mod pallet {
     #[pallet::storage]
     pub(super) type Kitties<T: Config> = StorageMap<_, Blake2_128Concat, [u8; 32], Kitty<T>>;
 
+    #[pallet::storage]
+    pub(super) type Paused<T: Config> = StorageValue<_, PauseScope, ValueQuery>;
+
     #[pallet::storage]
     pub(super) type KittiesOwned<T: Config> = StorageMap<
         _,
         Blake2_128Concat,
         T::AccountId,
-        BoundedVec<[u8; 32], ConstU32<100>>,
+        BoundedVec<[u8; 32], ConstU32<MAX_KITTIES_OWNED>>,
         ValueQuery,
     >;
 
+    /// Accounts that used their free first mint, with the block it happened in.
+    #[pallet::storage]
+    pub(super) type FreeMintClaimed<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, BlockNumberFor<T>>;
+
+    /// Number of free mints in a block, with the number of that block.
+    ///
+    /// A count left over from an earlier block counts as zero, see
+    /// [`Pallet::free_mints_in_block`]. Transaction pool validation never runs `on_initialize`,
+    /// so resetting the count there would make the pool see the previous block's count.
+    #[pallet::storage]
+    pub(super) type FreeMintsInBlock<T: Config> =
+        StorageValue<_, (BlockNumberFor<T>, u32), ValueQuery>;
+
+    /// Fee paid to `FeeDestination` for minting a kitty. Free mints don't pay it.
+    #[pallet::storage]
+    pub(super) type MintFee<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery>;
+
+    /// Cut of every sale price paid to `FeeDestination` instead of the seller.
+    #[pallet::storage]
+    pub(super) type MarketplaceFee<T: Config> = StorageValue<_, Permill, ValueQuery>;
+
+    #[pallet::genesis_config]
+    #[derive(frame_support::DefaultNoBound)]
+    pub struct GenesisConfig<T: Config> {
+        /// Kitties to mint at genesis.
+        ///
+        /// Kitties are appended to their owner's list in the order given here.
+        pub kitties: Vec<GenesisKitty<T>>,
+        /// The initial [`MintFee`].
+        pub mint_fee: BalanceOf<T>,
+        /// The initial [`MarketplaceFee`].
+        pub marketplace_fee: Permill,
+    }
+
+    #[pallet::genesis_build]
+    impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
+        fn build(&self) {
+            MintFee::<T>::put(self.mint_fee);
+            MarketplaceFee::<T>::put(self.marketplace_fee);
+            for (owner, dna, price) in &self.kitties {
+                Pallet::<T>::mint(owner.clone(), *dna).expect("genesis kitties must be mintable");
+                Kitties::<T>::mutate(dna, |kitty| {
+                    if let Some(kitty) = kitty {
+                        kitty.price = *price;
+                    }
+                });
+            }
+        }
+    }
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        #[cfg(feature = "try-runtime")]
+        fn try_state(_n: BlockNumberFor<T>) -> Result<(), sp_runtime::TryRuntimeError> {
+            Self::do_try_state()
+        }
+    }
+
     /// Events that functions in this pallet can emit.
     ///
     /// Events are a simple means of indicating to the outside world (such as dApps, chain explorers
@@ -121,10 +234,40 @@ pub mod pallet {
             new_price: Option<BalanceOf<T>>,
         },
         Sold {
+            seller: T::AccountId,
             buyer: T::AccountId,
             kitty_id: [u8; 32],
             price: BalanceOf<T>,
         },
+        /// A kitty was destroyed by its owner or by another pallet.
+        Burned {
+            owner: T::AccountId,
+            kitty_id: [u8; 32],
+        },
+        /// A kitty was moved to a new owner by the force origin.
+        ForceTransferred {
+            from: T::AccountId,
+            to: T::AccountId,
+            kitty_id: [u8; 32],
+        },
+        /// A kitty was destroyed by the force origin.
+        ForceBurned {
+            owner: T::AccountId,
+            kitty_id: [u8; 32],
+        },
+        /// The price of a kitty was changed by the force origin.
+        ForcePriceSet {
+            owner: T::AccountId,
+            kitty_id: [u8; 32],
+            new_price: Option<BalanceOf<T>>,
+        },
+        /// The parts of the marketplace that are halted have changed.
+        PauseSet { scope: PauseScope },
+        /// The mint and marketplace fees have changed.
+        FeesSet {
+            mint_fee: BalanceOf<T>,
+            marketplace_fee: Permill,
+        },
     }
 
     /// Errors that can be returned by this pallet.
@@ -149,6 +292,8 @@ pub mod pallet {
         TransferToSelf,
         NotForSale,
         MaxPriceTooLow,
+        /// This part of the marketplace is currently halted.
+        Paused,
     }
 
     /// The pallet's dispatchable functions ([`Call`]s).
@@ -165,27 +310,59 @@ pub mod pallet {
     /// The [`weight`] macro is used to assign a weight to each call.
     #[pallet::call]
     impl<T: Config> Pallet<T> {
-        /// An example dispatchable that takes a single u32 value as a parameter, writes the value
-        /// to storage and emits an event.
+        /// Mint a new kitty for the caller, with its DNA picked by `DnaGenerator`.
+        ///
+        /// The caller pays `MintFee` to `FeeDestination`, unless it has neither balance nor
+        /// kitties: such an account mints its first kitty for free, up to `MaxFreeMintsPerBlock`
+        /// of them per block. See [`Pallet::can_mint_for_free`]. Fails while minting is paused.
         #[pallet::call_index(0)]
-        #[pallet::weight(<T as pallet::Config>::WeightInfo::create_kitty())]
+        #[pallet::weight(
+            <T as pallet::Config>::WeightInfo::create_kitty()
+                .saturating_add(T::OnKittyCreated::weight())
+        )]
+        #[pallet::feeless_if(|origin: &OriginFor<T>| -> bool {
+            ensure_signed(origin.clone()).is_ok_and(|who| Pallet::<T>::can_mint_for_free(&who))
+        })]
         pub fn create_kitty(origin: OriginFor<T>) -> DispatchResult {
             let who = ensure_signed(origin)?;
-            let dna = Self::gen_dna();
-            Self::mint(who, dna)?;
+            ensure!(!Paused::<T>::get().minting, Error::<T>::Paused);
+            let free = Self::can_mint_for_free(&who);
+            if !free {
+                Self::pay_fee(&who, MintFee::<T>::get())?;
+            }
+            let dna = Self::gen_dna(&who);
+            Self::mint(who.clone(), dna)?;
+            if free {
+                Self::claim_free_mint(&who);
+            }
             Ok(())
         }
 
+        /// Transfer a kitty you own to `to`.
+        ///
+        /// Charges for the worst case of the sender owning the most kitties possible and the
+        /// recipient one less, the most it can own and still receive one, and refunds the
+        /// difference.
         #[pallet::call_index(1)]
-        #[pallet::weight(<T as pallet::Config>::WeightInfo::transfer())]
+        #[pallet::weight(
+            <T as pallet::Config>::WeightInfo::transfer(MAX_KITTIES_OWNED, MAX_KITTIES_OWNED - 1)
+                .saturating_add(T::OnKittyTransferred::weight())
+        )]
+        #[allow(clippy::useless_conversion)]
         pub fn transfer(
             origin: OriginFor<T>,
             to: T::AccountId,
             kitty_id: [u8; 32],
-        ) -> DispatchResult {
+        ) -> DispatchResultWithPostInfo {
             let from = ensure_signed(origin)?;
+            ensure!(!Paused::<T>::get().transfers, Error::<T>::Paused);
+            let from_owned = Self::owned_count(&from);
+            let to_owned = Self::owned_count(&to);
             Self::do_transfer(from, to, kitty_id)?;
-            Ok(())
+
+            let actual_weight = <T as pallet::Config>::WeightInfo::transfer(from_owned, to_owned)
+                .saturating_add(T::OnKittyTransferred::weight());
+            Ok(Some(actual_weight).into())
         }
 
         #[pallet::call_index(2)]
@@ -196,20 +373,113 @@ pub mod pallet {
             price: Option<BalanceOf<T>>,
         ) -> DispatchResult {
             let from = ensure_signed(origin)?;
+            // Delisting stays possible while sales are halted.
+            ensure!(
+                price.is_none() || !Paused::<T>::get().sales,
+                Error::<T>::Paused
+            );
 
             Self::do_set_price(from, kitty_id, price)?;
             Ok(())
         }
 
+        /// Buy a listed kitty, paying at most `max_price`.
+        ///
+        /// `MarketplaceFee` of the price goes to `FeeDestination`, the rest to the seller.
+        ///
+        /// Charges for the worst case of the seller owning the most kitties possible and the
+        /// buyer one less, the most it can own and still receive one, and refunds the
+        /// difference.
         #[pallet::call_index(3)]
-        #[pallet::weight(<T as pallet::Config>::WeightInfo::buy_kitty())]
+        #[pallet::weight(
+            <T as pallet::Config>::WeightInfo::buy_kitty(MAX_KITTIES_OWNED, MAX_KITTIES_OWNED - 1)
+                .saturating_add(T::OnKittyTransferred::weight())
+                .saturating_add(T::OnKittySold::weight())
+        )]
+        #[allow(clippy::useless_conversion)]
         pub fn buy_kitty(
             origin: OriginFor<T>,
             kitty_id: [u8; 32],
             max_price: BalanceOf<T>,
-        ) -> DispatchResult {
+        ) -> DispatchResultWithPostInfo {
             let from = ensure_signed(origin)?;
+            ensure!(!Paused::<T>::get().sales, Error::<T>::Paused);
+            let seller_owned =
+                Self::kitty(kitty_id).map_or(0, |kitty| Self::owned_count(&kitty.owner));
+            let buyer_owned = Self::owned_count(&from);
             Self::do_buy_kitty(from, kitty_id, max_price)?;
+
+            let actual_weight =
+                <T as pallet::Config>::WeightInfo::buy_kitty(seller_owned, buyer_owned)
+                    .saturating_add(T::OnKittyTransferred::weight())
+                    .saturating_add(T::OnKittySold::weight());
+            Ok(Some(actual_weight).into())
+        }
+
+        /// Move a kitty to `to` regardless of its current owner. Any listing is removed.
+        #[pallet::call_index(4)]
+        #[pallet::weight(
+            <T as pallet::Config>::WeightInfo::force_transfer()
+                .saturating_add(T::OnKittyTransferred::weight())
+        )]
+        pub fn force_transfer(
+            origin: OriginFor<T>,
+            to: T::AccountId,
+            kitty_id: [u8; 32],
+        ) -> DispatchResult {
+            T::ForceOrigin::ensure_origin(origin)?;
+            Self::do_force_transfer(to, kitty_id)?;
+            Ok(())
+        }
+
+        /// Destroy a kitty.
+        #[pallet::call_index(5)]
+        #[pallet::weight(<T as pallet::Config>::WeightInfo::force_burn())]
+        pub fn force_burn(origin: OriginFor<T>, kitty_id: [u8; 32]) -> DispatchResult {
+            T::ForceOrigin::ensure_origin(origin)?;
+            Self::do_force_burn(kitty_id)?;
+            Ok(())
+        }
+
+        /// Set or clear the price of a kitty on behalf of its owner.
+        #[pallet::call_index(6)]
+        #[pallet::weight(<T as pallet::Config>::WeightInfo::force_set_price())]
+        pub fn force_set_price(
+            origin: OriginFor<T>,
+            kitty_id: [u8; 32],
+            price: Option<BalanceOf<T>>,
+        ) -> DispatchResult {
+            T::ForceOrigin::ensure_origin(origin)?;
+            Self::do_force_set_price(kitty_id, price)?;
+            Ok(())
+        }
+
+        /// Replace the set of halted marketplace operations with `scope`.
+        #[pallet::call_index(7)]
+        #[pallet::weight(<T as pallet::Config>::WeightInfo::set_paused())]
+        pub fn set_paused(origin: OriginFor<T>, scope: PauseScope) -> DispatchResult {
+            T::PauseOrigin::ensure_origin(origin)?;
+            Paused::<T>::put(scope);
+            Self::deposit_event(Event::<T>::PauseSet { scope });
+            Ok(())
+        }
+
+        /// Set the fee for minting a kitty and the cut of every sale price that goes to
+        /// `FeeDestination`.
+        #[pallet::call_index(8)]
+        #[pallet::weight(<T as pallet::Config>::WeightInfo::set_fees())]
+        pub fn set_fees(
+            origin: OriginFor<T>,
+            mint_fee: BalanceOf<T>,
+            marketplace_fee: Permill,
+        ) -> DispatchResult {
+            T::ForceOrigin::ensure_origin(origin)?;
+            MintFee::<T>::put(mint_fee);
+            MarketplaceFee::<T>::put(marketplace_fee);
+            Self::deposit_event(Event::<T>::FeesSet {
+                mint_fee,
+                marketplace_fee,
+            });
             Ok(())
         }
     }
@@ -1,7 +1,7 @@
 #![cfg(test)]
 
 use crate as pallet_kitties;
-use frame_support::{construct_runtime, derive_impl};
+use frame_support::{construct_runtime, derive_impl, parameter_types, weights::Weight};
 use sp_runtime::BuildStorage;
 
 type Balance = u64;
@@ -24,21 +24,100 @@ impl frame_system::Config for TestRuntime {
 impl pallet_balances::Config for TestRuntime {
     type AccountStore = System;
     type Balance = Balance;
+    type ExistentialDeposit = ExistentialDeposit;
 }
 
 impl pallet_kitties::Config for TestRuntime {
     type RuntimeEvent = RuntimeEvent;
     type NativeCurrency = PalletBalances;
     type WeightInfo = ();
+    type ForceOrigin = frame_system::EnsureRoot<u64>;
+    type PauseOrigin = frame_system::EnsureRoot<u64>;
+    type OnKittyCreated = RecordHooks;
+    type OnKittyTransferred = RecordHooks;
+    type OnKittySold = RecordHooks;
+    type DnaGenerator = pallet_kitties::SequentialDna;
+    type MaxFreeMintsPerBlock = frame_support::traits::ConstU32<2>;
+    type FeeDestination = FeeDestination;
+}
+
+/// A call to one of the kitty hooks, as recorded by [`RecordHooks`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum HookCall {
+    Created(u64, [u8; 32]),
+    Transferred(u64, u64, [u8; 32]),
+    Sold(u64, u64, [u8; 32], Balance),
+}
+
+parameter_types! {
+    pub static HookCalls: Vec<HookCall> = Vec::new();
+    pub static ExistentialDeposit: Balance = 1;
+    pub const FeeDestination: u64 = 99;
+    pub const HookWeight: Weight = Weight::from_parts(1_000, 10);
+}
+
+/// Records every hook call into [`HookCalls`]. Every hook weighs [`HookWeight`].
+pub struct RecordHooks;
+
+impl pallet_kitties::OnKittyCreated<u64> for RecordHooks {
+    fn weight() -> Weight {
+        HookWeight::get()
+    }
+
+    fn on_kitty_created(owner: &u64, kitty_id: &[u8; 32]) {
+        HookCalls::mutate(|calls| calls.push(HookCall::Created(*owner, *kitty_id)));
+    }
+}
+
+impl pallet_kitties::OnKittyTransferred<u64> for RecordHooks {
+    fn weight() -> Weight {
+        HookWeight::get()
+    }
+
+    fn on_kitty_transferred(from: &u64, to: &u64, kitty_id: &[u8; 32]) {
+        HookCalls::mutate(|calls| calls.push(HookCall::Transferred(*from, *to, *kitty_id)));
+    }
+}
+
+impl pallet_kitties::OnKittySold<u64, Balance> for RecordHooks {
+    fn weight() -> Weight {
+        HookWeight::get()
+    }
+
+    fn on_kitty_sold(seller: &u64, buyer: &u64, kitty_id: &[u8; 32], price: Balance) {
+        HookCalls::mutate(|calls| calls.push(HookCall::Sold(*seller, *buyer, *kitty_id, price)));
+    }
 }
 
 // We need to run most of our tests using this function: `new_test_ext().execute_with(|| { ... });`
 // It simulates the blockchain database backend for our tests.
 // If you forget to include this and try to access your Pallet storage, you will get an error like:
 // "`get_version_1` called outside of an Externalities-provided environment."
-pub fn new_test_ext() -> sp_io::TestExternalities {
+//
+// Every test run through it also checks the pallet's storage invariants at the end, see
+// `Pallet::do_try_state`.
+pub fn new_test_ext() -> TestExternalities {
+    TestExternalities(new_test_ext_unchecked())
+}
+
+/// Like [`new_test_ext`], but without the invariant checks. Only for tests that write
+/// inconsistent storage on purpose.
+pub fn new_test_ext_unchecked() -> sp_io::TestExternalities {
     frame_system::GenesisConfig::<TestRuntime>::default()
         .build_storage()
         .unwrap()
         .into()
 }
+
+/// Test externalities that check the pallet's invariants after each execution.
+pub struct TestExternalities(sp_io::TestExternalities);
+
+impl TestExternalities {
+    pub fn execute_with<R>(&mut self, execute: impl FnOnce() -> R) -> R {
+        self.0.execute_with(|| {
+            let result = execute();
+            PalletKitties::do_try_state().expect("kitty storage invariants hold");
+            result
+        })
+    }
+}
@@ -11,13 +11,13 @@
 // This flag tells rust to only run this file when running `cargo test`.
 #![cfg(test)]
 
-use crate::mock::{new_test_ext, RuntimeEvent, RuntimeOrigin, TestRuntime};
+use crate::mock::{new_test_ext, new_test_ext_unchecked, RuntimeEvent, RuntimeOrigin, TestRuntime};
 use crate::*;
 use codec::{Decode, Encode, MaxEncodedLen};
-use frame_support::{assert_noop, assert_ok};
-use mock::{PalletBalances, PalletKitties, System};
+use frame_support::{assert_noop, assert_ok, dispatch::GetDispatchInfo, BoundedVec};
+use mock::{ExistentialDeposit, FeeDestination, PalletBalances, PalletKitties, System};
 use scale_info::TypeInfo;
-use sp_runtime::{ArithmeticError, DispatchError};
+use sp_runtime::{ArithmeticError, DispatchError, DispatchResult, Permill};
 
 type Balance = u64;
 type Block = frame_system::mocking::MockBlock<TestRuntime>;
@@ -115,7 +115,8 @@ fn create_kitty_emits_event() {
 
 #[test]
 fn count_for_kitties_created_correctly() {
-    new_test_ext().execute_with(|| {
+    // Writes storage directly, leaving it inconsistent on purpose.
+    new_test_ext_unchecked().execute_with(|| {
         assert_eq!(CountForKitties::<TestRuntime>::get(), u32::default());
 
         // A bit awkward... what is the difference between these 2?
@@ -145,7 +146,8 @@ fn mint_increment_counter() {
 
 #[test]
 fn mint_error_on_overflow() {
-    new_test_ext().execute_with(|| {
+    // Writes storage directly, leaving it inconsistent on purpose.
+    new_test_ext_unchecked().execute_with(|| {
         CountForKitties::<TestRuntime>::put(u32::MAX);
 
         assert_noop!(
@@ -158,7 +160,8 @@ fn mint_error_on_overflow() {
 // copied
 #[test]
 fn kitties_map_created_correctly() {
-    new_test_ext().execute_with(|| {
+    // Writes storage directly, leaving it inconsistent on purpose.
+    new_test_ext_unchecked().execute_with(|| {
         let zero_key = [0u8; 32];
         assert!(!Kitties::<TestRuntime>::contains_key(zero_key));
         Kitties::<TestRuntime>::insert(zero_key, DEFAULT_KITTY);
@@ -398,6 +401,7 @@ fn do_buy_kitty_emits_event() {
         // Assert the last event by our blockchain is the `Created` event with the correct owner.
         System::assert_last_event(
             Event::<TestRuntime>::Sold {
+                seller: ALICE,
                 buyer: BOB,
                 kitty_id,
                 price: 1337,
@@ -475,3 +479,1103 @@ fn do_buy_kitty_logic_works() {
         assert_eq!(PalletBalances::free_balance(&BOB), 100_000);
     })
 }
+
+#[test]
+fn fees_below_existential_deposit_are_burnt_until_the_fee_destination_exists() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        ExistentialDeposit::set(10);
+        assert_ok!(PalletKitties::set_fees(
+            RuntimeOrigin::root(),
+            0,
+            Permill::from_percent(10)
+        ));
+        let treasury = FeeDestination::get();
+        fund(ALICE, 1000);
+        fund(BOB, 1000);
+        assert_ok!(PalletKitties::create_kitty(RuntimeOrigin::signed(ALICE)));
+        let kitty_id = KittiesOwned::<TestRuntime>::get(ALICE)[0];
+        let sell = |seller, buyer| {
+            assert_ok!(PalletKitties::set_price(
+                RuntimeOrigin::signed(seller),
+                kitty_id,
+                Some(50)
+            ));
+            assert_ok!(PalletKitties::buy_kitty(
+                RuntimeOrigin::signed(buyer),
+                kitty_id,
+                50
+            ));
+        };
+
+        // The fee of 5 can't create the fee destination, so the sale burns it.
+        let issuance = PalletBalances::total_issuance();
+        sell(ALICE, BOB);
+        assert_eq!(PalletBalances::free_balance(BOB), 950);
+        assert_eq!(PalletBalances::free_balance(ALICE), 1045);
+        assert_eq!(PalletBalances::free_balance(treasury), 0);
+        assert_eq!(PalletBalances::total_issuance(), issuance - 5);
+
+        // Once the fee destination exists, it collects any fee.
+        fund(treasury, 10);
+        sell(BOB, ALICE);
+        assert_eq!(PalletBalances::free_balance(treasury), 15);
+    })
+}
+
+#[test]
+fn set_fees_works() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_eq!(MintFee::<TestRuntime>::get(), 0);
+        assert_eq!(MarketplaceFee::<TestRuntime>::get(), Permill::zero());
+
+        assert_ok!(PalletKitties::set_fees(
+            RuntimeOrigin::root(),
+            25,
+            Permill::from_percent(3)
+        ));
+        assert_eq!(MintFee::<TestRuntime>::get(), 25);
+        assert_eq!(
+            MarketplaceFee::<TestRuntime>::get(),
+            Permill::from_percent(3)
+        );
+        System::assert_last_event(
+            Event::<TestRuntime>::FeesSet {
+                mint_fee: 25,
+                marketplace_fee: Permill::from_percent(3),
+            }
+            .into(),
+        );
+    })
+}
+
+#[test]
+fn set_fees_requires_force_origin() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            PalletKitties::set_fees(RuntimeOrigin::signed(ALICE), 25, Permill::from_percent(3)),
+            DispatchError::BadOrigin
+        );
+        assert_noop!(
+            PalletKitties::set_fees(RuntimeOrigin::none(), 25, Permill::from_percent(3)),
+            DispatchError::BadOrigin
+        );
+    })
+}
+
+#[test]
+fn fees_go_to_fee_destination() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(PalletKitties::set_fees(
+            RuntimeOrigin::root(),
+            10,
+            Permill::from_percent(10)
+        ));
+        let treasury = FeeDestination::get();
+
+        // Free mints don't pay the mint fee.
+        assert_ok!(PalletKitties::create_kitty(RuntimeOrigin::signed(5)));
+        assert_eq!(PalletBalances::free_balance(treasury), 0);
+        // Any other mint does.
+        assert_noop!(
+            PalletKitties::create_kitty(RuntimeOrigin::signed(5)),
+            ArithmeticError::Underflow
+        );
+        fund(ALICE, 1000);
+        fund(BOB, 1000);
+        assert_ok!(PalletKitties::create_kitty(RuntimeOrigin::signed(ALICE)));
+        assert_eq!(PalletBalances::free_balance(ALICE), 990);
+        assert_eq!(PalletBalances::free_balance(treasury), 10);
+
+        // The marketplace takes its cut of the sale price from what the seller gets.
+        let kitty_id = KittiesOwned::<TestRuntime>::get(ALICE)[0];
+        assert_ok!(PalletKitties::set_price(
+            RuntimeOrigin::signed(ALICE),
+            kitty_id,
+            Some(200)
+        ));
+        assert_ok!(PalletKitties::buy_kitty(
+            RuntimeOrigin::signed(BOB),
+            kitty_id,
+            200
+        ));
+        assert_eq!(PalletBalances::free_balance(BOB), 800);
+        assert_eq!(PalletBalances::free_balance(ALICE), 1170);
+        assert_eq!(PalletBalances::free_balance(treasury), 30);
+        System::assert_last_event(
+            Event::<TestRuntime>::Sold {
+                seller: ALICE,
+                buyer: BOB,
+                kitty_id,
+                price: 200,
+            }
+            .into(),
+        );
+    })
+}
+
+#[test]
+fn query_helpers_work() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(PalletKitties::kitty_count(), 0);
+        assert!(PalletKitties::kitty([1u8; 32]).is_none());
+
+        assert_ok!(PalletKitties::mint(ALICE, [1u8; 32]));
+        assert_ok!(PalletKitties::mint(ALICE, [2u8; 32]));
+        assert_ok!(PalletKitties::mint(BOB, [3u8; 32]));
+        assert_eq!(PalletKitties::kitty_count(), 3);
+        assert_eq!(PalletKitties::kitty([3u8; 32]).unwrap().owner, BOB);
+
+        let alice_kitties = PalletKitties::kitties_of(&ALICE)
+            .into_iter()
+            .map(|kitty| kitty.dna)
+            .collect::<Vec<_>>();
+        assert_eq!(alice_kitties, vec![[1u8; 32], [2u8; 32]]);
+    })
+}
+
+#[test]
+fn listed_kitties_pages_through_listings() {
+    new_test_ext().execute_with(|| {
+        for i in 0..5u8 {
+            assert_ok!(PalletKitties::mint(ALICE, [i; 32]));
+        }
+        // Only kitties with a price are listed.
+        for i in 1..5u8 {
+            assert_ok!(PalletKitties::set_price(
+                RuntimeOrigin::signed(ALICE),
+                [i; 32],
+                Some(i.into())
+            ));
+        }
+
+        let first_page = PalletKitties::listed_kitties(None, 3);
+        assert_eq!(first_page.len(), 3);
+        let last = first_page.last().unwrap().dna;
+        let second_page = PalletKitties::listed_kitties(Some(last), 3);
+        assert_eq!(second_page.len(), 1);
+
+        let mut listed = first_page
+            .iter()
+            .chain(second_page.iter())
+            .map(|kitty| kitty.dna)
+            .collect::<Vec<_>>();
+        listed.sort();
+        assert_eq!(listed, vec![[1u8; 32], [2u8; 32], [3u8; 32], [4u8; 32]]);
+    })
+}
+
+#[test]
+fn force_calls_require_force_origin() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(PalletKitties::mint(ALICE, [1u8; 32]));
+        assert_noop!(
+            PalletKitties::force_transfer(RuntimeOrigin::signed(ALICE), BOB, [1u8; 32]),
+            DispatchError::BadOrigin
+        );
+        assert_noop!(
+            PalletKitties::force_burn(RuntimeOrigin::signed(ALICE), [1u8; 32]),
+            DispatchError::BadOrigin
+        );
+        assert_noop!(
+            PalletKitties::force_set_price(RuntimeOrigin::signed(ALICE), [1u8; 32], Some(1)),
+            DispatchError::BadOrigin
+        );
+    })
+}
+
+#[test]
+fn force_transfer_works() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(PalletKitties::mint(ALICE, [1u8; 32]));
+        assert_ok!(PalletKitties::set_price(
+            RuntimeOrigin::signed(ALICE),
+            [1u8; 32],
+            Some(1337)
+        ));
+        // Cannot move a kitty to its current owner or move a non-existent kitty.
+        assert_noop!(
+            PalletKitties::force_transfer(RuntimeOrigin::root(), ALICE, [1u8; 32]),
+            Error::<TestRuntime>::TransferToSelf
+        );
+        assert_noop!(
+            PalletKitties::force_transfer(RuntimeOrigin::root(), BOB, [0u8; 32]),
+            Error::<TestRuntime>::NoKitty
+        );
+
+        assert_ok!(PalletKitties::force_transfer(
+            RuntimeOrigin::root(),
+            BOB,
+            [1u8; 32]
+        ));
+        System::assert_last_event(
+            Event::<TestRuntime>::ForceTransferred {
+                from: ALICE,
+                to: BOB,
+                kitty_id: [1u8; 32],
+            }
+            .into(),
+        );
+        let kitty = Kitties::<TestRuntime>::get([1u8; 32]).unwrap();
+        assert_eq!(kitty.owner, BOB);
+        // The listing of the previous owner is dropped.
+        assert_eq!(kitty.price, None);
+        assert_eq!(KittiesOwned::<TestRuntime>::get(ALICE), vec![]);
+        assert_eq!(KittiesOwned::<TestRuntime>::get(BOB), vec![[1u8; 32]]);
+    })
+}
+
+#[test]
+fn force_burn_works() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(PalletKitties::mint(ALICE, [1u8; 32]));
+        assert_ok!(PalletKitties::mint(ALICE, [2u8; 32]));
+        assert_noop!(
+            PalletKitties::force_burn(RuntimeOrigin::root(), [0u8; 32]),
+            Error::<TestRuntime>::NoKitty
+        );
+
+        assert_ok!(PalletKitties::force_burn(RuntimeOrigin::root(), [1u8; 32]));
+        System::assert_last_event(
+            Event::<TestRuntime>::ForceBurned {
+                owner: ALICE,
+                kitty_id: [1u8; 32],
+            }
+            .into(),
+        );
+        assert!(!Kitties::<TestRuntime>::contains_key([1u8; 32]));
+        assert_eq!(KittiesOwned::<TestRuntime>::get(ALICE), vec![[2u8; 32]]);
+        assert_eq!(CountForKitties::<TestRuntime>::get(), 1);
+    })
+}
+
+#[test]
+fn force_set_price_works() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(PalletKitties::mint(ALICE, [1u8; 32]));
+        assert_noop!(
+            PalletKitties::force_set_price(RuntimeOrigin::root(), [0u8; 32], Some(1)),
+            Error::<TestRuntime>::NoKitty
+        );
+
+        assert_ok!(PalletKitties::force_set_price(
+            RuntimeOrigin::root(),
+            [1u8; 32],
+            Some(1337)
+        ));
+        System::assert_last_event(
+            Event::<TestRuntime>::ForcePriceSet {
+                owner: ALICE,
+                kitty_id: [1u8; 32],
+                new_price: Some(1337),
+            }
+            .into(),
+        );
+        assert_eq!(
+            Kitties::<TestRuntime>::get([1u8; 32]).unwrap().price,
+            Some(1337)
+        );
+    })
+}
+
+#[test]
+fn set_paused_requires_pause_origin() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let scope = PauseScope {
+            minting: true,
+            ..Default::default()
+        };
+        assert_noop!(
+            PalletKitties::set_paused(RuntimeOrigin::signed(ALICE), scope),
+            DispatchError::BadOrigin
+        );
+        assert_ok!(PalletKitties::set_paused(RuntimeOrigin::root(), scope));
+        System::assert_last_event(Event::<TestRuntime>::PauseSet { scope }.into());
+        assert_eq!(Paused::<TestRuntime>::get(), scope);
+    })
+}
+
+#[test]
+fn pause_scopes_are_independent() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(PalletKitties::mint(ALICE, [1u8; 32]));
+        assert_ok!(PalletKitties::set_price(
+            RuntimeOrigin::signed(ALICE),
+            [1u8; 32],
+            Some(1337)
+        ));
+        assert_ok!(PalletBalances::force_set_balance(
+            RuntimeOrigin::root(),
+            BOB,
+            100_000
+        ));
+
+        // Halting minting leaves the rest of the marketplace untouched.
+        assert_ok!(PalletKitties::set_paused(
+            RuntimeOrigin::root(),
+            PauseScope {
+                minting: true,
+                ..Default::default()
+            }
+        ));
+        assert_noop!(
+            PalletKitties::create_kitty(RuntimeOrigin::signed(ALICE)),
+            Error::<TestRuntime>::Paused
+        );
+        assert_ok!(PalletKitties::mint(ALICE, [2u8; 32]));
+        assert_ok!(PalletKitties::transfer(
+            RuntimeOrigin::signed(ALICE),
+            BOB,
+            [2u8; 32]
+        ));
+
+        // Halting transfers doesn't stop sales.
+        assert_ok!(PalletKitties::set_paused(
+            RuntimeOrigin::root(),
+            PauseScope {
+                transfers: true,
+                ..Default::default()
+            }
+        ));
+        assert_noop!(
+            PalletKitties::transfer(RuntimeOrigin::signed(BOB), ALICE, [2u8; 32]),
+            Error::<TestRuntime>::Paused
+        );
+        assert_ok!(PalletKitties::buy_kitty(
+            RuntimeOrigin::signed(BOB),
+            [1u8; 32],
+            1337
+        ));
+
+        // Halting sales stops listing and buying, but delisting stays possible.
+        assert_ok!(PalletKitties::set_paused(
+            RuntimeOrigin::root(),
+            PauseScope {
+                sales: true,
+                ..Default::default()
+            }
+        ));
+        assert_noop!(
+            PalletKitties::set_price(RuntimeOrigin::signed(BOB), [1u8; 32], Some(1)),
+            Error::<TestRuntime>::Paused
+        );
+        assert_ok!(PalletKitties::set_price(
+            RuntimeOrigin::signed(BOB),
+            [1u8; 32],
+            None
+        ));
+        assert_noop!(
+            PalletKitties::buy_kitty(RuntimeOrigin::signed(ALICE), [1u8; 32], 1337),
+            Error::<TestRuntime>::Paused
+        );
+        assert_ok!(PalletKitties::transfer(
+            RuntimeOrigin::signed(BOB),
+            ALICE,
+            [2u8; 32]
+        ));
+    })
+}
+
+#[test]
+fn nonfungible_traits_work() {
+    use frame_support::traits::tokens::nonfungible_v2::{Inspect, Mutate, Transfer};
+
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(<PalletKitties as Mutate<_, _>>::mint_into(
+            &[1u8; 32],
+            &ALICE,
+            &(),
+            false
+        ));
+        assert_eq!(PalletKitties::owner(&[1u8; 32]), Some(ALICE));
+        assert!(PalletKitties::can_transfer(&[1u8; 32]));
+        assert!(!PalletKitties::can_transfer(&[2u8; 32]));
+
+        assert_ok!(<PalletKitties as Transfer<_>>::transfer(&[1u8; 32], &BOB));
+        assert_eq!(PalletKitties::owner(&[1u8; 32]), Some(BOB));
+        assert_eq!(KittiesOwned::<TestRuntime>::get(BOB), vec![[1u8; 32]]);
+
+        // Burning checks the owner when asked to.
+        assert_noop!(
+            <PalletKitties as Mutate<_, _>>::burn(&[1u8; 32], Some(&ALICE)),
+            Error::<TestRuntime>::NotOwner
+        );
+        assert_ok!(<PalletKitties as Mutate<_, _>>::burn(
+            &[1u8; 32],
+            Some(&BOB)
+        ));
+        System::assert_last_event(
+            Event::<TestRuntime>::Burned {
+                owner: BOB,
+                kitty_id: [1u8; 32],
+            }
+            .into(),
+        );
+        assert_eq!(PalletKitties::owner(&[1u8; 32]), None);
+        assert_eq!(CountForKitties::<TestRuntime>::get(), 0);
+        assert_eq!(KittiesOwned::<TestRuntime>::get(BOB), vec![]);
+    })
+}
+
+#[test]
+fn nonfungible_transfer_removes_listing() {
+    use frame_support::traits::tokens::nonfungible_v2::Transfer;
+
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(PalletKitties::mint(ALICE, [1u8; 32]));
+        assert_ok!(PalletKitties::set_price(
+            RuntimeOrigin::signed(ALICE),
+            [1u8; 32],
+            Some(10)
+        ));
+
+        // E.g. moving the kitty into an escrow.
+        assert_ok!(<PalletKitties as Transfer<_>>::transfer(&[1u8; 32], &BOB));
+        assert_eq!(Kitties::<TestRuntime>::get([1u8; 32]).unwrap().price, None);
+        System::assert_last_event(
+            Event::<TestRuntime>::PriceSet {
+                owner: BOB,
+                kitty_id: [1u8; 32],
+                new_price: None,
+            }
+            .into(),
+        );
+
+        fund(3, 1000);
+        assert_noop!(
+            PalletKitties::buy_kitty(RuntimeOrigin::signed(3), [1u8; 32], 10),
+            Error::<TestRuntime>::NotForSale
+        );
+    })
+}
+
+#[test]
+fn nonfungible_traits_respect_pause() {
+    use frame_support::traits::tokens::nonfungible_v2::{Inspect, Mutate, Transfer};
+
+    new_test_ext().execute_with(|| {
+        assert_ok!(PalletKitties::mint(ALICE, [1u8; 32]));
+        assert_ok!(PalletKitties::set_paused(
+            RuntimeOrigin::root(),
+            PauseScope {
+                minting: true,
+                transfers: true,
+                sales: false,
+            }
+        ));
+
+        assert!(!PalletKitties::can_transfer(&[1u8; 32]));
+        assert_noop!(
+            <PalletKitties as Transfer<_>>::transfer(&[1u8; 32], &BOB),
+            Error::<TestRuntime>::Paused
+        );
+        assert_noop!(
+            <PalletKitties as Mutate<_, _>>::mint_into(&[2u8; 32], &ALICE, &(), false),
+            Error::<TestRuntime>::Paused
+        );
+    })
+}
+
+#[test]
+fn hooks_are_notified() {
+    use mock::{HookCall, HookCalls};
+
+    new_test_ext().execute_with(|| {
+        assert_ok!(PalletKitties::mint(ALICE, [1u8; 32]));
+        assert_ok!(PalletKitties::transfer(
+            RuntimeOrigin::signed(ALICE),
+            BOB,
+            [1u8; 32]
+        ));
+        assert_ok!(PalletKitties::set_price(
+            RuntimeOrigin::signed(BOB),
+            [1u8; 32],
+            Some(1337)
+        ));
+        assert_ok!(PalletBalances::force_set_balance(
+            RuntimeOrigin::root(),
+            ALICE,
+            100_000
+        ));
+        assert_ok!(PalletKitties::buy_kitty(
+            RuntimeOrigin::signed(ALICE),
+            [1u8; 32],
+            1337
+        ));
+
+        assert_eq!(
+            HookCalls::get(),
+            vec![
+                HookCall::Created(ALICE, [1u8; 32]),
+                HookCall::Transferred(ALICE, BOB, [1u8; 32]),
+                HookCall::Transferred(BOB, ALICE, [1u8; 32]),
+                HookCall::Sold(BOB, ALICE, [1u8; 32], 1337),
+            ]
+        );
+    })
+}
+
+#[test]
+fn hooks_are_not_notified_on_failure() {
+    use mock::HookCalls;
+
+    new_test_ext().execute_with(|| {
+        assert_ok!(PalletKitties::mint(ALICE, [1u8; 32]));
+        HookCalls::set(vec![]);
+        assert_noop!(
+            PalletKitties::transfer(RuntimeOrigin::signed(BOB), ALICE, [1u8; 32]),
+            Error::<TestRuntime>::NotOwner
+        );
+        assert_noop!(
+            PalletKitties::mint(BOB, [1u8; 32]),
+            Error::<TestRuntime>::DuplicatedKitty
+        );
+        assert_eq!(HookCalls::get(), vec![]);
+    })
+}
+
+#[test]
+fn calls_charge_for_the_hooks_they_run() {
+    use frame_support::weights::Weight;
+    use mock::{HookWeight, RecordHooks};
+
+    let hook = HookWeight::get();
+    let weight = |call: Call<TestRuntime>| call.get_dispatch_info().weight;
+    assert_eq!(
+        weight(Call::create_kitty {}),
+        <() as WeightInfo>::create_kitty() + hook
+    );
+    assert_eq!(
+        weight(Call::transfer {
+            to: BOB,
+            kitty_id: [1u8; 32]
+        }),
+        <() as WeightInfo>::transfer(MAX_KITTIES_OWNED, MAX_KITTIES_OWNED - 1) + hook
+    );
+    assert_eq!(
+        weight(Call::buy_kitty {
+            kitty_id: [1u8; 32],
+            max_price: 1337
+        }),
+        <() as WeightInfo>::buy_kitty(MAX_KITTIES_OWNED, MAX_KITTIES_OWNED - 1) + hook * 2
+    );
+    assert_eq!(
+        weight(Call::force_transfer {
+            to: BOB,
+            kitty_id: [1u8; 32]
+        }),
+        <() as WeightInfo>::force_transfer() + hook
+    );
+
+    // Combined hooks charge for all of their members.
+    assert_eq!(
+        <(RecordHooks, RecordHooks) as OnKittySold<u64, u64>>::weight(),
+        hook * 2
+    );
+    assert_eq!(<() as OnKittySold<u64, u64>>::weight(), Weight::zero());
+}
+
+#[test]
+fn create_kitty_uses_dna_generator() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        // The mock runtime derives the DNA from the kitty count only.
+        let first_dna = sp_io::hashing::blake2_256(&0u32.encode());
+        let second_dna = sp_io::hashing::blake2_256(&1u32.encode());
+
+        assert_ok!(PalletKitties::create_kitty(RuntimeOrigin::signed(ALICE)));
+        System::assert_last_event(
+            Event::<TestRuntime>::Created {
+                owner: ALICE,
+                kitty_id: first_dna,
+            }
+            .into(),
+        );
+        assert_ok!(PalletKitties::create_kitty(RuntimeOrigin::signed(BOB)));
+        assert_eq!(KittiesOwned::<TestRuntime>::get(BOB), vec![second_dna]);
+    })
+}
+
+#[test]
+fn transfer_and_buy_refund_unused_weight() {
+    use mock::HookWeight;
+
+    new_test_ext().execute_with(|| {
+        assert_ok!(PalletKitties::mint(ALICE, [1u8; 32]));
+        assert_ok!(PalletKitties::mint(ALICE, [2u8; 32]));
+        assert_ok!(PalletKitties::mint(BOB, [3u8; 32]));
+
+        let post_info =
+            PalletKitties::transfer(RuntimeOrigin::signed(ALICE), BOB, [1u8; 32]).unwrap();
+        assert_eq!(
+            post_info.actual_weight,
+            Some(<() as WeightInfo>::transfer(2, 1) + HookWeight::get())
+        );
+        // Less than the worst case charged up front.
+        let worst_case = <() as WeightInfo>::transfer(MAX_KITTIES_OWNED, MAX_KITTIES_OWNED - 1);
+        assert!(post_info.actual_weight.unwrap().ref_time() < worst_case.ref_time());
+
+        assert_ok!(PalletKitties::set_price(
+            RuntimeOrigin::signed(BOB),
+            [3u8; 32],
+            Some(1337)
+        ));
+        assert_ok!(PalletBalances::force_set_balance(
+            RuntimeOrigin::root(),
+            ALICE,
+            100_000
+        ));
+        let post_info =
+            PalletKitties::buy_kitty(RuntimeOrigin::signed(ALICE), [3u8; 32], 1337).unwrap();
+        assert_eq!(
+            post_info.actual_weight,
+            Some(<() as WeightInfo>::buy_kitty(2, 1) + HookWeight::get() * 2)
+        );
+    })
+}
+
+#[test]
+fn try_state_detects_broken_invariants() {
+    new_test_ext_unchecked().execute_with(|| {
+        assert_ok!(PalletKitties::create_kitty(RuntimeOrigin::signed(ALICE)));
+        assert_ok!(PalletKitties::do_try_state());
+
+        // Count out of sync with the kitties map.
+        CountForKitties::<TestRuntime>::put(2);
+        assert!(PalletKitties::do_try_state().is_err());
+        CountForKitties::<TestRuntime>::put(1);
+
+        // Kitty missing from its owner's index.
+        let kitty_id = KittiesOwned::<TestRuntime>::take(ALICE)[0];
+        assert!(PalletKitties::do_try_state().is_err());
+
+        // Kitty listed under an account that does not own it.
+        KittiesOwned::<TestRuntime>::insert(ALICE, BoundedVec::truncate_from(vec![kitty_id]));
+        KittiesOwned::<TestRuntime>::insert(BOB, BoundedVec::truncate_from(vec![kitty_id]));
+        assert!(PalletKitties::do_try_state().is_err());
+        KittiesOwned::<TestRuntime>::remove(BOB);
+
+        // Index pointing to a kitty that does not exist.
+        KittiesOwned::<TestRuntime>::insert(BOB, BoundedVec::truncate_from(vec![[7u8; 32]]));
+        assert!(PalletKitties::do_try_state().is_err());
+        KittiesOwned::<TestRuntime>::remove(BOB);
+
+        assert_ok!(PalletKitties::do_try_state());
+    })
+}
+
+#[test]
+fn check_kitty_purchase_rejects_doomed_buys() {
+    use sp_runtime::traits::SignedExtension;
+    use sp_runtime::transaction_validity::InvalidTransaction;
+
+    new_test_ext().execute_with(|| {
+        let buy = |kitty_id, max_price| {
+            let call = mock::RuntimeCall::PalletKitties(Call::buy_kitty {
+                kitty_id,
+                max_price,
+            });
+            CheckKittyPurchase::<TestRuntime>::new().validate(&BOB, &call, &Default::default(), 0)
+        };
+        let rejected = |code: PurchaseValidity| Err(InvalidTransaction::Custom(code as u8).into());
+
+        assert_eq!(buy([0u8; 32], 100), rejected(PurchaseValidity::NoKitty));
+
+        assert_ok!(PalletKitties::create_kitty(RuntimeOrigin::signed(ALICE)));
+        let kitty_id = KittiesOwned::<TestRuntime>::get(ALICE)[0];
+        assert_eq!(buy(kitty_id, 100), rejected(PurchaseValidity::NotForSale));
+
+        assert_ok!(PalletKitties::set_price(
+            RuntimeOrigin::signed(ALICE),
+            kitty_id,
+            Some(50)
+        ));
+        assert_eq!(
+            buy(kitty_id, 49),
+            rejected(PurchaseValidity::MaxPriceTooLow)
+        );
+        assert!(buy(kitty_id, 50).is_ok());
+
+        assert_ok!(PalletKitties::set_paused(
+            RuntimeOrigin::root(),
+            PauseScope {
+                sales: true,
+                ..Default::default()
+            }
+        ));
+        assert_eq!(buy(kitty_id, 50), rejected(PurchaseValidity::SalesPaused));
+
+        // Other calls are not affected.
+        let call = mock::RuntimeCall::PalletKitties(Call::create_kitty {});
+        assert!(CheckKittyPurchase::<TestRuntime>::new()
+            .validate(&BOB, &call, &Default::default(), 0)
+            .is_ok());
+    })
+}
+
+#[test]
+fn first_mint_is_free_for_new_accounts() {
+    use frame_support::dispatch::CheckIfFeeless;
+
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let call = mock::RuntimeCall::PalletKitties(Call::create_kitty {});
+        let feeless = |who: u64| call.is_feeless(&RuntimeOrigin::signed(who));
+
+        // Accounts holding tokens pay.
+        assert_ok!(PalletBalances::force_set_balance(
+            RuntimeOrigin::root(),
+            BOB,
+            100
+        ));
+        assert!(!feeless(BOB));
+
+        // Only the first kitty is free, even after giving it away.
+        assert!(feeless(ALICE));
+        assert_ok!(PalletKitties::create_kitty(RuntimeOrigin::signed(ALICE)));
+        assert_eq!(FreeMintClaimed::<TestRuntime>::get(ALICE), Some(1));
+        assert!(!feeless(ALICE));
+        let kitty_id = KittiesOwned::<TestRuntime>::get(ALICE)[0];
+        assert_ok!(PalletKitties::transfer(
+            RuntimeOrigin::signed(ALICE),
+            BOB,
+            kitty_id
+        ));
+        assert!(!feeless(ALICE));
+
+        // `MaxFreeMintsPerBlock` is 2 in the mock.
+        assert_ok!(PalletKitties::create_kitty(RuntimeOrigin::signed(3)));
+        assert!(!feeless(4));
+        System::set_block_number(2);
+        assert!(feeless(4));
+
+        // Nothing is free while minting is paused.
+        assert_ok!(PalletKitties::set_paused(
+            RuntimeOrigin::root(),
+            PauseScope {
+                minting: true,
+                ..Default::default()
+            }
+        ));
+        assert!(!feeless(4));
+    })
+}
+
+#[test]
+fn free_mints_reset_without_on_initialize() {
+    use frame_support::dispatch::CheckIfFeeless;
+    use sp_runtime::traits::SignedExtension;
+
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let call = mock::RuntimeCall::PalletKitties(Call::create_kitty {});
+
+        // `MaxFreeMintsPerBlock` is 2 in the mock.
+        assert_ok!(PalletKitties::create_kitty(RuntimeOrigin::signed(3)));
+        assert_ok!(PalletKitties::create_kitty(RuntimeOrigin::signed(4)));
+        assert!(!call.is_feeless(&RuntimeOrigin::signed(5)));
+
+        // The pool validates against the next block number, without running any hooks.
+        System::set_block_number(2);
+        assert!(call.is_feeless(&RuntimeOrigin::signed(5)));
+        assert_ok!(CheckFreeMint::<TestRuntime>::new().validate(&5, &call, &Default::default(), 0));
+        assert_ok!(frame_system::CheckNonce::<TestRuntime>::from(0).validate(
+            &5,
+            &call,
+            &Default::default(),
+            0
+        ));
+    })
+}
+
+#[test]
+fn check_free_mint_holds_back_mints_beyond_the_block_allowance() {
+    use sp_runtime::{
+        traits::SignedExtension,
+        transaction_validity::{InvalidTransaction, TransactionValidityError},
+    };
+
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let call = mock::RuntimeCall::PalletKitties(Call::create_kitty {});
+        let check = CheckFreeMint::<TestRuntime>::new();
+
+        // Free mints get no priority and don't stay in the pool for long.
+        let valid = check.validate(&3, &call, &Default::default(), 0).unwrap();
+        assert_eq!(valid.priority, 0);
+        assert_eq!(valid.longevity, extensions::FREE_MINT_LONGEVITY);
+
+        // `MaxFreeMintsPerBlock` is 2 in the mock.
+        assert_ok!(PalletKitties::create_kitty(RuntimeOrigin::signed(3)));
+        assert_ok!(PalletKitties::create_kitty(RuntimeOrigin::signed(4)));
+        let exhausted = TransactionValidityError::Invalid(InvalidTransaction::ExhaustsResources);
+        assert_eq!(
+            check.validate(&5, &call, &Default::default(), 0),
+            Err(exhausted)
+        );
+        assert_eq!(
+            check
+                .clone()
+                .pre_dispatch(&5, &call, &Default::default(), 0)
+                .map(|_| ()),
+            Err(exhausted)
+        );
+        assert_eq!(System::sufficients(&5), 0);
+
+        // Accounts that pay for their mint are not held back.
+        assert_ok!(PalletBalances::force_set_balance(
+            RuntimeOrigin::root(),
+            ALICE,
+            100
+        ));
+        assert_ok!(check.validate(&ALICE, &call, &Default::default(), 0));
+
+        // The next block has room again.
+        System::set_block_number(2);
+        assert_ok!(check.validate(&5, &call, &Default::default(), 0));
+    })
+}
+
+#[test]
+fn check_free_mint_limits_free_mints_in_the_pool() {
+    use frame_support::storage::{with_transaction, TransactionOutcome};
+    use frame_system::CheckNonce;
+    use sp_runtime::traits::SignedExtension;
+
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let create = mock::RuntimeCall::PalletKitties(Call::create_kitty {});
+        // The tags a transaction pool sees, validating on a throwaway state like the pool does.
+        let provides = |who: u64, nonce: u32| {
+            with_transaction(|| {
+                let mut tags = CheckFreeMint::<TestRuntime>::new()
+                    .validate(&who, &create, &Default::default(), 0)
+                    .unwrap()
+                    .provides;
+                let nonce = CheckNonce::<TestRuntime>::from(nonce)
+                    .validate(&who, &create, &Default::default(), 0)
+                    .unwrap();
+                tags.extend(nonce.provides);
+                TransactionOutcome::Rollback(Ok::<_, DispatchError>(tags))
+            })
+            .unwrap()
+        };
+        let shared = |a: &Vec<Vec<u8>>, b: &Vec<Vec<u8>>| a.iter().any(|tag| b.contains(tag));
+
+        // A fresh account can't queue a second free mint under another nonce.
+        assert!(shared(&provides(5, 0), &provides(5, 1)));
+
+        // `MaxFreeMintsPerBlock` is 2 in the mock, so of any three fresh accounts two compete
+        // for the same slot.
+        let accounts = [5, 6, 7].map(|who| provides(who, 0));
+        assert!(
+            shared(&accounts[0], &accounts[1])
+                || shared(&accounts[0], &accounts[2])
+                || shared(&accounts[1], &accounts[2])
+        );
+
+        // Paid mints are not limited.
+        fund(ALICE, 100);
+        assert!(CheckFreeMint::<TestRuntime>::new()
+            .validate(&ALICE, &create, &Default::default(), 0)
+            .unwrap()
+            .provides
+            .is_empty());
+    })
+}
+
+#[test]
+fn check_free_mint_provides_new_accounts() {
+    use sp_runtime::traits::SignedExtension;
+
+    new_test_ext().execute_with(|| {
+        let create = mock::RuntimeCall::PalletKitties(Call::create_kitty {});
+        let check = |who: u64, call: &mock::RuntimeCall| {
+            assert_ok!(CheckFreeMint::<TestRuntime>::new().pre_dispatch(
+                &who,
+                call,
+                &Default::default(),
+                0
+            ));
+            System::sufficients(&who)
+        };
+
+        // Other calls and accounts that already exist are left alone.
+        let set_price = mock::RuntimeCall::PalletKitties(Call::set_price {
+            kitty_id: [0u8; 32],
+            price: None,
+        });
+        assert_eq!(check(ALICE, &set_price), 0);
+        assert_ok!(PalletBalances::force_set_balance(
+            RuntimeOrigin::root(),
+            BOB,
+            100
+        ));
+        assert_eq!(check(BOB, &create), 0);
+
+        // A brand-new account gets a reference so its nonce can be stored.
+        assert_eq!(check(ALICE, &create), 1);
+        assert_ok!(PalletKitties::create_kitty(RuntimeOrigin::signed(ALICE)));
+        assert_eq!(check(ALICE, &create), 1);
+    })
+}
+
+#[test]
+fn check_free_mint_keeps_reference_if_mint_fails() {
+    use sp_runtime::traits::SignedExtension;
+
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let create = mock::RuntimeCall::PalletKitties(Call::create_kitty {});
+        let pre_dispatch = |who: u64| {
+            CheckFreeMint::<TestRuntime>::new()
+                .pre_dispatch(&who, &create, &Default::default(), 0)
+                .unwrap()
+        };
+        let post_dispatch = |pre: Option<u64>, result: &DispatchResult| {
+            assert_ok!(CheckFreeMint::<TestRuntime>::post_dispatch(
+                Some(pre),
+                &Default::default(),
+                &Default::default(),
+                0,
+                result
+            ));
+        };
+
+        // A successful free mint keeps the reference.
+        let pre = pre_dispatch(3);
+        let result = PalletKitties::create_kitty(RuntimeOrigin::signed(3));
+        assert!(result.is_ok());
+        post_dispatch(pre, &result);
+        assert_eq!(System::sufficients(&3), 1);
+
+        // Minting is paused between validation and dispatch.
+        let pre = pre_dispatch(5);
+        assert_eq!(System::sufficients(&5), 1);
+        assert_ok!(PalletKitties::set_paused(
+            RuntimeOrigin::root(),
+            PauseScope {
+                minting: true,
+                ..Default::default()
+            }
+        ));
+        let result = PalletKitties::create_kitty(RuntimeOrigin::signed(5));
+        assert!(result.is_err());
+        post_dispatch(pre, &result);
+        assert_eq!(System::sufficients(&5), 1);
+        assert!(!PalletKitties::can_mint_for_free(&5));
+    })
+}
+
+#[test]
+fn check_free_mint_rejects_replayed_failed_mint() {
+    use frame_system::CheckNonce;
+    use sp_runtime::{
+        traits::SignedExtension,
+        transaction_validity::{InvalidTransaction, TransactionValidity, TransactionValidityError},
+    };
+
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let create = mock::RuntimeCall::PalletKitties(Call::create_kitty {});
+        let info = create.get_dispatch_info();
+        let validate = || -> TransactionValidity {
+            CheckFreeMint::<TestRuntime>::new().validate(&5, &create, &info, 0)?;
+            CheckNonce::<TestRuntime>::from(0).validate(&5, &create, &info, 0)
+        };
+
+        // The free mint fails at dispatch.
+        let pre = CheckFreeMint::<TestRuntime>::new()
+            .pre_dispatch(&5, &create, &info, 0)
+            .unwrap();
+        assert_ok!(CheckNonce::<TestRuntime>::from(0).pre_dispatch(&5, &create, &info, 0));
+        assert_ok!(PalletKitties::set_paused(
+            RuntimeOrigin::root(),
+            PauseScope {
+                minting: true,
+                ..Default::default()
+            }
+        ));
+        let result = PalletKitties::create_kitty(RuntimeOrigin::signed(5));
+        assert!(result.is_err());
+        assert_ok!(CheckFreeMint::<TestRuntime>::post_dispatch(
+            Some(pre),
+            &info,
+            &Default::default(),
+            0,
+            &result
+        ));
+
+        // The account and its nonce survive, so the same transaction is stale.
+        assert_eq!(
+            validate(),
+            Err(TransactionValidityError::Invalid(InvalidTransaction::Stale))
+        );
+        // Nor can the account mint for free again once minting resumes.
+        assert_ok!(PalletKitties::set_paused(
+            RuntimeOrigin::root(),
+            PauseScope::default()
+        ));
+        assert!(!PalletKitties::can_mint_for_free(&5));
+    })
+}
+
+#[test]
+fn genesis_config_mints_kitties() {
+    use sp_runtime::BuildStorage;
+
+    let mut storage = frame_system::GenesisConfig::<TestRuntime>::default()
+        .build_storage()
+        .unwrap();
+    GenesisConfig::<TestRuntime> {
+        kitties: vec![
+            (ALICE, [1u8; 32], None),
+            (BOB, [2u8; 32], Some(10)),
+            (ALICE, [3u8; 32], Some(5)),
+        ],
+        mint_fee: 7,
+        marketplace_fee: Permill::from_percent(2),
+    }
+    .assimilate_storage(&mut storage)
+    .unwrap();
+
+    sp_io::TestExternalities::from(storage).execute_with(|| {
+        assert_eq!(CountForKitties::<TestRuntime>::get(), 3);
+        assert_eq!(
+            KittiesOwned::<TestRuntime>::get(ALICE),
+            vec![[1u8; 32], [3u8; 32]]
+        );
+        assert_eq!(KittiesOwned::<TestRuntime>::get(BOB), vec![[2u8; 32]]);
+        assert_eq!(Kitties::<TestRuntime>::get([1u8; 32]).unwrap().price, None);
+        assert_eq!(
+            Kitties::<TestRuntime>::get([2u8; 32]).unwrap().price,
+            Some(10)
+        );
+        assert_eq!(
+            Kitties::<TestRuntime>::get([3u8; 32]).unwrap().price,
+            Some(5)
+        );
+        assert_eq!(MintFee::<TestRuntime>::get(), 7);
+        assert_eq!(
+            MarketplaceFee::<TestRuntime>::get(),
+            Permill::from_percent(2)
+        );
+        assert_ok!(PalletKitties::do_try_state());
+    });
+}
+
+fn fund(who: u64, amount: Balance) {
+    assert_ok!(PalletBalances::force_set_balance(
+        RuntimeOrigin::root(),
+        who,
+        amount
+    ));
+}
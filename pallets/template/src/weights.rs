@@ -2,19 +2,24 @@
 //! Autogenerated weights for `pallet_template`
 //!
 //! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 43.0.0
-//! DATE: 2024-12-02, STEPS: `50`, REPEAT: `20`, LOW RANGE: `[]`, HIGH RANGE: `[]`
+//! DATE: 2026-10-19, STEPS: `50`, REPEAT: `20`, LOW RANGE: `[]`, HIGH RANGE: `[]`
 //! WORST CASE MAP SIZE: `1000000`
-//! HOSTNAME: `madalosso`, CPU: `12th Gen Intel(R) Core(TM) i5-12400`
+//! HOSTNAME: `vm`, CPU: `Intel(R) Xeon(R) Processor`
 //! WASM-EXECUTION: `Compiled`, CHAIN: `Some("dev")`, DB CACHE: `1024`
 
 // Executed Command:
 // ./target/release/solochain-template-node
 // benchmark
 // pallet
+// --chain=dev
 // --pallet
 // pallet_template
-// --extrinsic=*
-// --chain=dev
+// --extrinsic
+// *
+// --steps
+// 50
+// --repeat
+// 20
 // --output
 // pallets/template/src/weights.rs
 // --template
@@ -31,128 +36,311 @@ use core::marker::PhantomData;
 /// Weight functions needed for `pallet_template`.
 pub trait WeightInfo {
 	fn create_kitty() -> Weight;
-	fn transfer() -> Weight;
+	fn transfer(f: u32, t: u32, ) -> Weight;
 	fn set_price() -> Weight;
-	fn buy_kitty() -> Weight;
+	fn buy_kitty(s: u32, b: u32, ) -> Weight;
+	fn force_transfer() -> Weight;
+	fn force_burn() -> Weight;
+	fn force_set_price() -> Weight;
+	fn set_paused() -> Weight;
+	fn set_fees() -> Weight;
 }
 
 /// Weights for `pallet_template` using the Substrate node and recommended hardware.
 pub struct SubstrateWeight<T>(PhantomData<T>);
 impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: `TemplateModule::Paused` (r:1 w:0)
+	/// Proof: `TemplateModule::Paused` (`max_values`: Some(1), `max_size`: Some(3), added: 498, mode: `MaxEncodedLen`)
+	/// Storage: `TemplateModule::FreeMintsInBlock` (r:1 w:0)
+	/// Proof: `TemplateModule::FreeMintsInBlock` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `TemplateModule::FreeMintClaimed` (r:1 w:0)
+	/// Proof: `TemplateModule::FreeMintClaimed` (`max_values`: None, `max_size`: Some(52), added: 2527, mode: `MaxEncodedLen`)
+	/// Storage: `TemplateModule::KittiesOwned` (r:1 w:1)
+	/// Proof: `TemplateModule::KittiesOwned` (`max_values`: None, `max_size`: Some(3250), added: 5725, mode: `MaxEncodedLen`)
+	/// Storage: `TemplateModule::MintFee` (r:1 w:0)
+	/// Proof: `TemplateModule::MintFee` (`max_values`: Some(1), `max_size`: Some(16), added: 511, mode: `MaxEncodedLen`)
+	/// Storage: `System::Account` (r:1 w:1)
+	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
 	/// Storage: `System::ParentHash` (r:1 w:0)
 	/// Proof: `System::ParentHash` (`max_values`: Some(1), `max_size`: Some(32), added: 527, mode: `MaxEncodedLen`)
 	/// Storage: `TemplateModule::CountForKitties` (r:1 w:1)
 	/// Proof: `TemplateModule::CountForKitties` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
 	/// Storage: `TemplateModule::Kitties` (r:1 w:1)
 	/// Proof: `TemplateModule::Kitties` (`max_values`: None, `max_size`: Some(129), added: 2604, mode: `MaxEncodedLen`)
-	/// Storage: `TemplateModule::KittiesOwned` (r:1 w:1)
-	/// Proof: `TemplateModule::KittiesOwned` (`max_values`: None, `max_size`: Some(3250), added: 5725, mode: `MaxEncodedLen`)
 	fn create_kitty() -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `6`
+		//  Measured:  `208`
 		//  Estimated: `6715`
-		// Minimum execution time: 30_456_000 picoseconds.
-		Weight::from_parts(35_149_000, 6715)
-			.saturating_add(T::DbWeight::get().reads(4_u64))
-			.saturating_add(T::DbWeight::get().writes(3_u64))
+		// Minimum execution time: 106_988_000 picoseconds.
+		Weight::from_parts(110_003_000, 6715)
+			.saturating_add(T::DbWeight::get().reads(9_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
 	}
-	/// Storage: `TemplateModule::Kitties` (r:1 w:1)
-	/// Proof: `TemplateModule::Kitties` (`max_values`: None, `max_size`: Some(129), added: 2604, mode: `MaxEncodedLen`)
+	/// Storage: `TemplateModule::Paused` (r:1 w:0)
+	/// Proof: `TemplateModule::Paused` (`max_values`: Some(1), `max_size`: Some(3), added: 498, mode: `MaxEncodedLen`)
 	/// Storage: `TemplateModule::KittiesOwned` (r:2 w:2)
 	/// Proof: `TemplateModule::KittiesOwned` (`max_values`: None, `max_size`: Some(3250), added: 5725, mode: `MaxEncodedLen`)
-	fn transfer() -> Weight {
+	/// Storage: `TemplateModule::Kitties` (r:1 w:1)
+	/// Proof: `TemplateModule::Kitties` (`max_values`: None, `max_size`: Some(129), added: 2604, mode: `MaxEncodedLen`)
+	/// The range of component `f` is `[1, 100]`.
+	/// The range of component `t` is `[0, 99]`.
+	fn transfer(f: u32, t: u32, ) -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `276`
+		//  Measured:  `902 + f * (33 ±0) + t * (33 ±0)`
 		//  Estimated: `12440`
-		// Minimum execution time: 39_397_000 picoseconds.
-		Weight::from_parts(43_902_000, 12440)
-			.saturating_add(T::DbWeight::get().reads(3_u64))
+		// Minimum execution time: 69_152_000 picoseconds.
+		Weight::from_parts(48_461_292, 12440)
+			// Standard Error: 28_335
+			.saturating_add(Weight::from_parts(411_068, 0).saturating_mul(f.into()))
+			// Standard Error: 28_335
+			.saturating_add(Weight::from_parts(479_045, 0).saturating_mul(t.into()))
+			.saturating_add(T::DbWeight::get().reads(4_u64))
 			.saturating_add(T::DbWeight::get().writes(3_u64))
 	}
+	/// Storage: `TemplateModule::Paused` (r:1 w:0)
+	/// Proof: `TemplateModule::Paused` (`max_values`: Some(1), `max_size`: Some(3), added: 498, mode: `MaxEncodedLen`)
 	/// Storage: `TemplateModule::Kitties` (r:1 w:1)
 	/// Proof: `TemplateModule::Kitties` (`max_values`: None, `max_size`: Some(129), added: 2604, mode: `MaxEncodedLen`)
 	fn set_price() -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `204`
+		//  Measured:  `334`
 		//  Estimated: `3594`
-		// Minimum execution time: 23_713_000 picoseconds.
-		Weight::from_parts(25_505_000, 3594)
-			.saturating_add(T::DbWeight::get().reads(1_u64))
+		// Minimum execution time: 28_621_000 picoseconds.
+		Weight::from_parts(39_741_000, 3594)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
 			.saturating_add(T::DbWeight::get().writes(1_u64))
 	}
+	/// Storage: `TemplateModule::Paused` (r:1 w:0)
+	/// Proof: `TemplateModule::Paused` (`max_values`: Some(1), `max_size`: Some(3), added: 498, mode: `MaxEncodedLen`)
 	/// Storage: `TemplateModule::Kitties` (r:1 w:1)
 	/// Proof: `TemplateModule::Kitties` (`max_values`: None, `max_size`: Some(129), added: 2604, mode: `MaxEncodedLen`)
-	/// Storage: `System::Account` (r:1 w:1)
+	/// Storage: `TemplateModule::KittiesOwned` (r:2 w:2)
+	/// Proof: `TemplateModule::KittiesOwned` (`max_values`: None, `max_size`: Some(3250), added: 5725, mode: `MaxEncodedLen`)
+	/// Storage: `TemplateModule::MarketplaceFee` (r:1 w:0)
+	/// Proof: `TemplateModule::MarketplaceFee` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
+	/// Storage: `System::Account` (r:2 w:2)
 	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
+	/// The range of component `s` is `[1, 100]`.
+	/// The range of component `b` is `[0, 99]`.
+	fn buy_kitty(_s: u32, b: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1124 + b * (33 ±0) + s * (33 ±0)`
+		//  Estimated: `12440`
+		// Minimum execution time: 185_149_000 picoseconds.
+		Weight::from_parts(317_329_605, 12440)
+			// Standard Error: 73_327
+			.saturating_add(Weight::from_parts(612_514, 0).saturating_mul(b.into()))
+			.saturating_add(T::DbWeight::get().reads(7_u64))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+	}
+	/// Storage: `TemplateModule::Kitties` (r:1 w:1)
+	/// Proof: `TemplateModule::Kitties` (`max_values`: None, `max_size`: Some(129), added: 2604, mode: `MaxEncodedLen`)
 	/// Storage: `TemplateModule::KittiesOwned` (r:2 w:2)
 	/// Proof: `TemplateModule::KittiesOwned` (`max_values`: None, `max_size`: Some(3250), added: 5725, mode: `MaxEncodedLen`)
-	fn buy_kitty() -> Weight {
+	fn force_transfer() -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `395`
+		//  Measured:  `432`
 		//  Estimated: `12440`
-		// Minimum execution time: 97_203_000 picoseconds.
-		Weight::from_parts(121_916_000, 12440)
-			.saturating_add(T::DbWeight::get().reads(4_u64))
-			.saturating_add(T::DbWeight::get().writes(4_u64))
+		// Minimum execution time: 50_180_000 picoseconds.
+		Weight::from_parts(52_372_000, 12440)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	/// Storage: `TemplateModule::Kitties` (r:1 w:1)
+	/// Proof: `TemplateModule::Kitties` (`max_values`: None, `max_size`: Some(129), added: 2604, mode: `MaxEncodedLen`)
+	/// Storage: `TemplateModule::KittiesOwned` (r:1 w:1)
+	/// Proof: `TemplateModule::KittiesOwned` (`max_values`: None, `max_size`: Some(3250), added: 5725, mode: `MaxEncodedLen`)
+	/// Storage: `TemplateModule::CountForKitties` (r:1 w:1)
+	/// Proof: `TemplateModule::CountForKitties` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
+	fn force_burn() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `432`
+		//  Estimated: `6715`
+		// Minimum execution time: 42_695_000 picoseconds.
+		Weight::from_parts(44_498_000, 6715)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	/// Storage: `TemplateModule::Kitties` (r:1 w:1)
+	/// Proof: `TemplateModule::Kitties` (`max_values`: None, `max_size`: Some(129), added: 2604, mode: `MaxEncodedLen`)
+	fn force_set_price() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `334`
+		//  Estimated: `3594`
+		// Minimum execution time: 27_329_000 picoseconds.
+		Weight::from_parts(28_512_000, 3594)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `TemplateModule::Paused` (r:0 w:1)
+	/// Proof: `TemplateModule::Paused` (`max_values`: Some(1), `max_size`: Some(3), added: 498, mode: `MaxEncodedLen`)
+	fn set_paused() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 10_154_000 picoseconds.
+		Weight::from_parts(11_673_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `TemplateModule::MintFee` (r:0 w:1)
+	/// Proof: `TemplateModule::MintFee` (`max_values`: Some(1), `max_size`: Some(16), added: 511, mode: `MaxEncodedLen`)
+	/// Storage: `TemplateModule::MarketplaceFee` (r:0 w:1)
+	/// Proof: `TemplateModule::MarketplaceFee` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
+	fn set_fees() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 11_066_000 picoseconds.
+		Weight::from_parts(11_747_000, 0)
+			.saturating_add(T::DbWeight::get().writes(2_u64))
 	}
 }
 
 // For backwards compatibility and tests.
 impl WeightInfo for () {
+	/// Storage: `TemplateModule::Paused` (r:1 w:0)
+	/// Proof: `TemplateModule::Paused` (`max_values`: Some(1), `max_size`: Some(3), added: 498, mode: `MaxEncodedLen`)
+	/// Storage: `TemplateModule::FreeMintsInBlock` (r:1 w:0)
+	/// Proof: `TemplateModule::FreeMintsInBlock` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `TemplateModule::FreeMintClaimed` (r:1 w:0)
+	/// Proof: `TemplateModule::FreeMintClaimed` (`max_values`: None, `max_size`: Some(52), added: 2527, mode: `MaxEncodedLen`)
+	/// Storage: `TemplateModule::KittiesOwned` (r:1 w:1)
+	/// Proof: `TemplateModule::KittiesOwned` (`max_values`: None, `max_size`: Some(3250), added: 5725, mode: `MaxEncodedLen`)
+	/// Storage: `TemplateModule::MintFee` (r:1 w:0)
+	/// Proof: `TemplateModule::MintFee` (`max_values`: Some(1), `max_size`: Some(16), added: 511, mode: `MaxEncodedLen`)
+	/// Storage: `System::Account` (r:1 w:1)
+	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
 	/// Storage: `System::ParentHash` (r:1 w:0)
 	/// Proof: `System::ParentHash` (`max_values`: Some(1), `max_size`: Some(32), added: 527, mode: `MaxEncodedLen`)
 	/// Storage: `TemplateModule::CountForKitties` (r:1 w:1)
 	/// Proof: `TemplateModule::CountForKitties` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
 	/// Storage: `TemplateModule::Kitties` (r:1 w:1)
 	/// Proof: `TemplateModule::Kitties` (`max_values`: None, `max_size`: Some(129), added: 2604, mode: `MaxEncodedLen`)
-	/// Storage: `TemplateModule::KittiesOwned` (r:1 w:1)
-	/// Proof: `TemplateModule::KittiesOwned` (`max_values`: None, `max_size`: Some(3250), added: 5725, mode: `MaxEncodedLen`)
 	fn create_kitty() -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `6`
+		//  Measured:  `208`
 		//  Estimated: `6715`
-		// Minimum execution time: 30_456_000 picoseconds.
-		Weight::from_parts(35_149_000, 6715)
-			.saturating_add(RocksDbWeight::get().reads(4_u64))
-			.saturating_add(RocksDbWeight::get().writes(3_u64))
+		// Minimum execution time: 106_988_000 picoseconds.
+		Weight::from_parts(110_003_000, 6715)
+			.saturating_add(RocksDbWeight::get().reads(9_u64))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
 	}
-	/// Storage: `TemplateModule::Kitties` (r:1 w:1)
-	/// Proof: `TemplateModule::Kitties` (`max_values`: None, `max_size`: Some(129), added: 2604, mode: `MaxEncodedLen`)
+	/// Storage: `TemplateModule::Paused` (r:1 w:0)
+	/// Proof: `TemplateModule::Paused` (`max_values`: Some(1), `max_size`: Some(3), added: 498, mode: `MaxEncodedLen`)
 	/// Storage: `TemplateModule::KittiesOwned` (r:2 w:2)
 	/// Proof: `TemplateModule::KittiesOwned` (`max_values`: None, `max_size`: Some(3250), added: 5725, mode: `MaxEncodedLen`)
-	fn transfer() -> Weight {
+	/// Storage: `TemplateModule::Kitties` (r:1 w:1)
+	/// Proof: `TemplateModule::Kitties` (`max_values`: None, `max_size`: Some(129), added: 2604, mode: `MaxEncodedLen`)
+	/// The range of component `f` is `[1, 100]`.
+	/// The range of component `t` is `[0, 99]`.
+	fn transfer(f: u32, t: u32, ) -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `276`
+		//  Measured:  `902 + f * (33 ±0) + t * (33 ±0)`
 		//  Estimated: `12440`
-		// Minimum execution time: 39_397_000 picoseconds.
-		Weight::from_parts(43_902_000, 12440)
-			.saturating_add(RocksDbWeight::get().reads(3_u64))
+		// Minimum execution time: 69_152_000 picoseconds.
+		Weight::from_parts(48_461_292, 12440)
+			// Standard Error: 28_335
+			.saturating_add(Weight::from_parts(411_068, 0).saturating_mul(f.into()))
+			// Standard Error: 28_335
+			.saturating_add(Weight::from_parts(479_045, 0).saturating_mul(t.into()))
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
 			.saturating_add(RocksDbWeight::get().writes(3_u64))
 	}
+	/// Storage: `TemplateModule::Paused` (r:1 w:0)
+	/// Proof: `TemplateModule::Paused` (`max_values`: Some(1), `max_size`: Some(3), added: 498, mode: `MaxEncodedLen`)
 	/// Storage: `TemplateModule::Kitties` (r:1 w:1)
 	/// Proof: `TemplateModule::Kitties` (`max_values`: None, `max_size`: Some(129), added: 2604, mode: `MaxEncodedLen`)
 	fn set_price() -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `204`
+		//  Measured:  `334`
 		//  Estimated: `3594`
-		// Minimum execution time: 23_713_000 picoseconds.
-		Weight::from_parts(25_505_000, 3594)
-			.saturating_add(RocksDbWeight::get().reads(1_u64))
+		// Minimum execution time: 28_621_000 picoseconds.
+		Weight::from_parts(39_741_000, 3594)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
 			.saturating_add(RocksDbWeight::get().writes(1_u64))
 	}
+	/// Storage: `TemplateModule::Paused` (r:1 w:0)
+	/// Proof: `TemplateModule::Paused` (`max_values`: Some(1), `max_size`: Some(3), added: 498, mode: `MaxEncodedLen`)
 	/// Storage: `TemplateModule::Kitties` (r:1 w:1)
 	/// Proof: `TemplateModule::Kitties` (`max_values`: None, `max_size`: Some(129), added: 2604, mode: `MaxEncodedLen`)
-	/// Storage: `System::Account` (r:1 w:1)
+	/// Storage: `TemplateModule::KittiesOwned` (r:2 w:2)
+	/// Proof: `TemplateModule::KittiesOwned` (`max_values`: None, `max_size`: Some(3250), added: 5725, mode: `MaxEncodedLen`)
+	/// Storage: `TemplateModule::MarketplaceFee` (r:1 w:0)
+	/// Proof: `TemplateModule::MarketplaceFee` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
+	/// Storage: `System::Account` (r:2 w:2)
 	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
+	/// The range of component `s` is `[1, 100]`.
+	/// The range of component `b` is `[0, 99]`.
+	fn buy_kitty(_s: u32, b: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1124 + b * (33 ±0) + s * (33 ±0)`
+		//  Estimated: `12440`
+		// Minimum execution time: 185_149_000 picoseconds.
+		Weight::from_parts(317_329_605, 12440)
+			// Standard Error: 73_327
+			.saturating_add(Weight::from_parts(612_514, 0).saturating_mul(b.into()))
+			.saturating_add(RocksDbWeight::get().reads(7_u64))
+			.saturating_add(RocksDbWeight::get().writes(5_u64))
+	}
+	/// Storage: `TemplateModule::Kitties` (r:1 w:1)
+	/// Proof: `TemplateModule::Kitties` (`max_values`: None, `max_size`: Some(129), added: 2604, mode: `MaxEncodedLen`)
 	/// Storage: `TemplateModule::KittiesOwned` (r:2 w:2)
 	/// Proof: `TemplateModule::KittiesOwned` (`max_values`: None, `max_size`: Some(3250), added: 5725, mode: `MaxEncodedLen`)
-	fn buy_kitty() -> Weight {
+	fn force_transfer() -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `395`
+		//  Measured:  `432`
 		//  Estimated: `12440`
-		// Minimum execution time: 97_203_000 picoseconds.
-		Weight::from_parts(121_916_000, 12440)
-			.saturating_add(RocksDbWeight::get().reads(4_u64))
-			.saturating_add(RocksDbWeight::get().writes(4_u64))
+		// Minimum execution time: 50_180_000 picoseconds.
+		Weight::from_parts(52_372_000, 12440)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	/// Storage: `TemplateModule::Kitties` (r:1 w:1)
+	/// Proof: `TemplateModule::Kitties` (`max_values`: None, `max_size`: Some(129), added: 2604, mode: `MaxEncodedLen`)
+	/// Storage: `TemplateModule::KittiesOwned` (r:1 w:1)
+	/// Proof: `TemplateModule::KittiesOwned` (`max_values`: None, `max_size`: Some(3250), added: 5725, mode: `MaxEncodedLen`)
+	/// Storage: `TemplateModule::CountForKitties` (r:1 w:1)
+	/// Proof: `TemplateModule::CountForKitties` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
+	fn force_burn() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `432`
+		//  Estimated: `6715`
+		// Minimum execution time: 42_695_000 picoseconds.
+		Weight::from_parts(44_498_000, 6715)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	/// Storage: `TemplateModule::Kitties` (r:1 w:1)
+	/// Proof: `TemplateModule::Kitties` (`max_values`: None, `max_size`: Some(129), added: 2604, mode: `MaxEncodedLen`)
+	fn force_set_price() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `334`
+		//  Estimated: `3594`
+		// Minimum execution time: 27_329_000 picoseconds.
+		Weight::from_parts(28_512_000, 3594)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `TemplateModule::Paused` (r:0 w:1)
+	/// Proof: `TemplateModule::Paused` (`max_values`: Some(1), `max_size`: Some(3), added: 498, mode: `MaxEncodedLen`)
+	fn set_paused() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 10_154_000 picoseconds.
+		Weight::from_parts(11_673_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `TemplateModule::MintFee` (r:0 w:1)
+	/// Proof: `TemplateModule::MintFee` (`max_values`: Some(1), `max_size`: Some(16), added: 511, mode: `MaxEncodedLen`)
+	/// Storage: `TemplateModule::MarketplaceFee` (r:0 w:1)
+	/// Proof: `TemplateModule::MarketplaceFee` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
+	fn set_fees() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 11_066_000 picoseconds.
+		Weight::from_parts(11_747_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
 	}
 }
\ No newline at end of file
@@ -30,6 +30,7 @@ use frame_support::{
 	weights::Weight,
 };
 use pallet_grandpa::AuthorityId as GrandpaId;
+use pallet_template_runtime_api::{KittyEvent, KittyInfo};
 use sp_api::impl_runtime_apis;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
@@ -43,9 +44,39 @@ use sp_version::RuntimeVersion;
 // Local module imports
 use super::{
 	AccountId, Aura, Balance, Block, Executive, Grandpa, InherentDataExt, Nonce, Runtime,
-	RuntimeCall, RuntimeGenesisConfig, SessionKeys, System, TransactionPayment, VERSION,
+	RuntimeCall, RuntimeEvent, RuntimeGenesisConfig, SessionKeys, System, TemplateModule,
+	TransactionPayment, VERSION,
 };
 
+fn kitty_info(kitty: pallet_template::Kitty<Runtime>) -> KittyInfo<AccountId, Balance> {
+	KittyInfo { dna: kitty.dna, owner: kitty.owner, price: kitty.price }
+}
+
+fn kitty_event(event: pallet_template::Event<Runtime>) -> Option<KittyEvent<AccountId, Balance>> {
+	use pallet_template::Event;
+
+	match event {
+		Event::Created { owner, kitty_id } => Some(KittyEvent::Created { owner, kitty_id }),
+		Event::Transferred { from, to, kitty_id } =>
+			Some(KittyEvent::Transferred { from, to, kitty_id }),
+		Event::PriceSet { owner, kitty_id, new_price } =>
+			Some(KittyEvent::PriceSet { owner, kitty_id, new_price }),
+		Event::Sold { seller, buyer, kitty_id, price } =>
+			Some(KittyEvent::Sold { seller, buyer, kitty_id, price }),
+		Event::Burned { owner, kitty_id } => Some(KittyEvent::Burned { owner, kitty_id }),
+		Event::ForceTransferred { from, to, kitty_id } =>
+			Some(KittyEvent::ForceTransferred { from, to, kitty_id }),
+		Event::ForceBurned { owner, kitty_id } => Some(KittyEvent::ForceBurned { owner, kitty_id }),
+		Event::ForcePriceSet { owner, kitty_id, new_price } =>
+			Some(KittyEvent::ForcePriceSet { owner, kitty_id, new_price }),
+		// Not about any kitty. `__Ignore` is the uninhabited variant the pallet macro adds.
+		Event::SomethingStored { .. } |
+		Event::PauseSet { .. } |
+		Event::FeesSet { .. } |
+		Event::__Ignore(..) => None,
+	}
+}
+
 impl_runtime_apis! {
 	impl sp_api::Core<Block> for Runtime {
 		fn version() -> RuntimeVersion {
@@ -214,6 +245,38 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_template_runtime_api::KittiesApi<Block, AccountId, Balance> for Runtime {
+		fn kitty(dna: [u8; 32]) -> Option<KittyInfo<AccountId, Balance>> {
+			TemplateModule::kitty(dna).map(kitty_info)
+		}
+
+		fn kitties_of(account: AccountId) -> Vec<KittyInfo<AccountId, Balance>> {
+			TemplateModule::kitties_of(&account).into_iter().map(kitty_info).collect()
+		}
+
+		fn kitty_count() -> u32 {
+			TemplateModule::kitty_count()
+		}
+
+		fn listed_kitties(start: Option<[u8; 32]>, limit: u32) -> Vec<KittyInfo<AccountId, Balance>> {
+			TemplateModule::listed_kitties(start, limit).into_iter().map(kitty_info).collect()
+		}
+
+		fn kitty_events() -> Vec<KittyEvent<AccountId, Balance>> {
+			System::read_events_no_consensus()
+				.filter_map(|record| match record.event {
+					RuntimeEvent::TemplateModule(event) => kitty_event(event),
+					_ => None,
+				})
+				.collect()
+		}
+
+		fn kitty_image(dna: [u8; 32]) -> Option<Vec<u8>> {
+			TemplateModule::kitty(dna)
+				.map(|_| pallet_template_runtime_api::render_svg(&dna).into_bytes())
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn benchmark_metadata(extra: bool) -> (
@@ -27,7 +27,16 @@ frame_benchmarking::define_benchmarks!(
 	[frame_benchmarking, BaselineBench::<Runtime>]
 	[frame_system, SystemBench::<Runtime>]
 	[pallet_balances, Balances]
+	[pallet_collective, Council]
+	[pallet_conviction_voting, ConvictionVoting]
+	[pallet_multisig, Multisig]
+	[pallet_preimage, Preimage]
+	[pallet_proxy, Proxy]
+	[pallet_referenda, Referenda]
+	[pallet_scheduler, Scheduler]
 	[pallet_timestamp, Timestamp]
 	[pallet_sudo, Sudo]
 	[pallet_template, TemplateModule]
+	[pallet_treasury, Treasury]
+	[pallet_utility, Utility]
 );
@@ -0,0 +1,373 @@
+//! Tests of the runtime configuration.
+
+use codec::Encode;
+use frame_support::{
+	assert_noop, assert_ok,
+	dispatch::GetDispatchInfo,
+	traits::{tokens::nonfungible_v2::Inspect, InstanceFilter},
+	weights::Weight,
+};
+use pallet_template::{weights::SubstrateWeight, WeightInfo, MAX_KITTIES_OWNED};
+use sp_runtime::{
+	traits::{BlakeTwo256, Dispatchable, Hash},
+	BuildStorage, DispatchError,
+};
+
+use crate::{
+	configs::ProxyType, AccountId, Balance, Balances, Council, Multisig, Proxy, Runtime,
+	RuntimeCall, RuntimeEvent, RuntimeOrigin, System, TemplateCall, TemplateModule, UNIT,
+};
+
+const ALICE: AccountId = AccountId::new([1; 32]);
+const BOB: AccountId = AccountId::new([2; 32]);
+const CHARLIE: AccountId = AccountId::new([3; 32]);
+const DAVE: AccountId = AccountId::new([4; 32]);
+
+// A guild of three that co-owns kitties through a 2-of-3 multisig account.
+const GUILD: [AccountId; 3] = [ALICE, BOB, CHARLIE];
+const GUILD_THRESHOLD: u16 = 2;
+
+fn new_test_ext() -> sp_io::TestExternalities {
+	let storage = frame_system::GenesisConfig::<Runtime>::default().build_storage().unwrap();
+	let mut ext = sp_io::TestExternalities::new(storage);
+	ext.execute_with(|| {
+		System::set_block_number(1);
+		for who in [ALICE, BOB, CHARLIE] {
+			assert_ok!(Balances::force_set_balance(
+				RuntimeOrigin::root(),
+				who.into(),
+				1_000 * UNIT
+			));
+		}
+	});
+	ext
+}
+
+fn transfer() -> RuntimeCall {
+	RuntimeCall::TemplateModule(TemplateCall::transfer { to: BOB, kitty_id: [1; 32] })
+}
+
+fn buy_kitty() -> RuntimeCall {
+	RuntimeCall::TemplateModule(TemplateCall::buy_kitty { kitty_id: [3; 32], max_price: UNIT })
+}
+
+fn set_price() -> RuntimeCall {
+	RuntimeCall::TemplateModule(TemplateCall::set_price { kitty_id: [1; 32], price: Some(UNIT) })
+}
+
+fn delist() -> RuntimeCall {
+	RuntimeCall::TemplateModule(TemplateCall::set_price { kitty_id: [1; 32], price: None })
+}
+
+fn create_kitty() -> RuntimeCall {
+	RuntimeCall::TemplateModule(TemplateCall::create_kitty {})
+}
+
+fn force_transfer() -> RuntimeCall {
+	RuntimeCall::TemplateModule(TemplateCall::force_transfer { to: BOB, kitty_id: [1; 32] })
+}
+
+fn batch(calls: Vec<RuntimeCall>) -> RuntimeCall {
+	RuntimeCall::Utility(pallet_utility::Call::batch { calls })
+}
+
+fn owner(kitty_id: [u8; 32]) -> Option<AccountId> {
+	<TemplateModule as Inspect<AccountId>>::owner(&kitty_id)
+}
+
+/// Mints `count` kitties to `owner` and returns their DNA. `seed` keeps the DNA of different
+/// owners apart.
+fn mint_kitties(owner: AccountId, seed: u8, count: u8) -> Vec<[u8; 32]> {
+	(0..count)
+		.map(|index| {
+			let mut dna = [seed; 32];
+			dna[0] = index;
+			assert_ok!(TemplateModule::mint(owner.clone(), dna));
+			dna
+		})
+		.collect()
+}
+
+/// Dispatches `call` from `who` and returns the weight charged for it after the refund.
+fn charged_weight(call: RuntimeCall, who: AccountId) -> Weight {
+	let info = call.get_dispatch_info();
+	let post_info = call.dispatch(RuntimeOrigin::signed(who)).unwrap();
+	post_info.calc_actual_weight(&info)
+}
+
+fn fund(who: AccountId, amount: Balance) {
+	assert_ok!(Balances::force_set_balance(RuntimeOrigin::root(), who.into(), amount));
+}
+
+fn guild_account() -> AccountId {
+	Multisig::multi_account_id(&GUILD, GUILD_THRESHOLD)
+}
+
+// `member` approves `call` on behalf of the guild. The approval that reaches the threshold
+// dispatches the call.
+fn guild_approve(member: AccountId, call: TemplateCall<Runtime>) {
+	let call = RuntimeCall::TemplateModule(call);
+	let others = GUILD.into_iter().filter(|other| *other != member).collect();
+	// Only the first approval opens the operation, the others refer to it by the timepoint it was
+	// opened at. The tests never leave the block, so that is always the current one.
+	let pending = pallet_multisig::Multisigs::<Runtime>::iter_prefix(guild_account())
+		.next()
+		.map(|_| Multisig::timepoint());
+	assert_ok!(Multisig::as_multi(
+		RuntimeOrigin::signed(member),
+		GUILD_THRESHOLD,
+		others,
+		pending,
+		Box::new(call.clone()),
+		call.get_dispatch_info().weight,
+	));
+}
+
+#[test]
+fn proxy_types_filter_kitty_calls() {
+	let balance_transfer = RuntimeCall::Balances(pallet_balances::Call::transfer_allow_death {
+		dest: BOB.into(),
+		value: UNIT,
+	});
+	let calls = [
+		transfer(),
+		buy_kitty(),
+		set_price(),
+		delist(),
+		create_kitty(),
+		force_transfer(),
+		balance_transfer,
+	];
+
+	assert!(calls.iter().all(|call| ProxyType::Any.filter(call)));
+	assert_eq!(
+		calls.iter().map(|call| ProxyType::KittiesTrader.filter(call)).collect::<Vec<_>>(),
+		[false, true, true, true, false, false, false],
+	);
+	assert_eq!(
+		calls.iter().map(|call| ProxyType::KittiesNonTransfer.filter(call)).collect::<Vec<_>>(),
+		[false, false, false, true, false, false, false],
+	);
+	assert_eq!(ProxyType::default(), ProxyType::Any);
+}
+
+#[test]
+fn proxy_types_are_ordered_by_what_they_allow() {
+	use ProxyType::*;
+
+	assert!(Any.is_superset(&KittiesTrader));
+	assert!(Any.is_superset(&KittiesNonTransfer));
+	assert!(!KittiesTrader.is_superset(&Any));
+	assert!(KittiesTrader.is_superset(&KittiesNonTransfer));
+	assert!(!KittiesNonTransfer.is_superset(&KittiesTrader));
+}
+
+/// Batches pass the proxy filter, but every call in them is filtered again on dispatch.
+#[test]
+fn batches_do_not_bypass_the_proxy_filter() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::mint(ALICE, [1; 32]));
+		assert_ok!(TemplateModule::mint(CHARLIE, [3; 32]));
+		assert_ok!(TemplateModule::set_price(RuntimeOrigin::signed(CHARLIE), [3; 32], Some(UNIT)));
+		assert_ok!(Proxy::add_proxy(
+			RuntimeOrigin::signed(ALICE),
+			BOB.into(),
+			ProxyType::KittiesNonTransfer,
+			0
+		));
+
+		// Only the delisting goes through, everything else moves balances or kitties.
+		for call in [transfer(), force_transfer(), set_price(), buy_kitty(), create_kitty()] {
+			assert_ok!(TemplateModule::set_price(
+				RuntimeOrigin::signed(ALICE),
+				[1; 32],
+				Some(UNIT)
+			));
+			System::reset_events();
+			assert_ok!(Proxy::proxy(
+				RuntimeOrigin::signed(BOB),
+				ALICE.into(),
+				None,
+				Box::new(batch(vec![delist(), call])),
+			));
+			System::assert_has_event(RuntimeEvent::Utility(
+				pallet_utility::Event::BatchInterrupted {
+					index: 1,
+					error: frame_system::Error::<Runtime>::CallFiltered.into(),
+				},
+			));
+			assert_eq!(TemplateModule::kitty([1; 32]).unwrap().price, None);
+		}
+		assert_eq!(owner([1; 32]), Some(ALICE));
+		assert_eq!(owner([3; 32]), Some(CHARLIE));
+		assert_eq!(TemplateModule::owned_count(&ALICE), 1);
+	});
+}
+
+#[test]
+fn multisig_guild_mints_kitties() {
+	new_test_ext().execute_with(|| {
+		guild_approve(ALICE, TemplateCall::create_kitty {});
+		// One approval is not enough.
+		assert_eq!(TemplateModule::owned_count(&guild_account()), 0);
+
+		guild_approve(BOB, TemplateCall::create_kitty {});
+		assert_eq!(TemplateModule::owned_count(&guild_account()), 1);
+		// The deposit of the pending approval is returned.
+		assert_eq!(Balances::free_balance(ALICE), 1_000 * UNIT);
+	});
+}
+
+#[test]
+fn multisig_guild_kitties_only_move_with_enough_approvals() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::mint(guild_account(), [1; 32]));
+
+		// No single member owns the kitty.
+		assert_noop!(
+			TemplateModule::transfer(RuntimeOrigin::signed(ALICE), DAVE, [1; 32]),
+			pallet_template::Error::<Runtime>::NotOwner
+		);
+
+		let transfer = TemplateCall::transfer { to: DAVE, kitty_id: [1; 32] };
+		guild_approve(ALICE, transfer.clone());
+		assert_eq!(owner([1; 32]), Some(guild_account()));
+		guild_approve(CHARLIE, transfer);
+		assert_eq!(owner([1; 32]), Some(DAVE));
+	});
+}
+
+#[test]
+fn multisig_guild_trades_kitties() {
+	new_test_ext().execute_with(|| {
+		fund(guild_account(), 1_000 * UNIT);
+		fund(DAVE, 1_000 * UNIT);
+
+		// The guild buys a kitty listed by someone else, paying from the shared account.
+		assert_ok!(TemplateModule::mint(DAVE, [4; 32]));
+		assert_ok!(TemplateModule::set_price(
+			RuntimeOrigin::signed(DAVE),
+			[4; 32],
+			Some(300 * UNIT)
+		));
+		let buy = TemplateCall::buy_kitty { kitty_id: [4; 32], max_price: 300 * UNIT };
+		guild_approve(ALICE, buy.clone());
+		guild_approve(BOB, buy);
+		assert_eq!(owner([4; 32]), Some(guild_account()));
+		assert_eq!(Balances::free_balance(guild_account()), 700 * UNIT);
+
+		// And lists it again, for someone to buy it from the guild.
+		let list = TemplateCall::set_price { kitty_id: [4; 32], price: Some(500 * UNIT) };
+		guild_approve(BOB, list.clone());
+		guild_approve(ALICE, list);
+		assert_ok!(TemplateModule::buy_kitty(RuntimeOrigin::signed(DAVE), [4; 32], 500 * UNIT));
+		assert_eq!(owner([4; 32]), Some(DAVE));
+		assert_eq!(Balances::free_balance(guild_account()), 1_200 * UNIT);
+	});
+}
+
+#[test]
+fn council_motions_dispatch_force_calls() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::mint(CHARLIE, [1; 32]));
+		assert_ok!(Council::set_members(RuntimeOrigin::root(), vec![ALICE, BOB, CHARLIE], None, 0));
+		let proposal = force_transfer();
+		let length = proposal.encoded_size() as u32;
+		let hash = BlakeTwo256::hash_of(&proposal);
+
+		// A single member is not enough.
+		assert_ok!(Council::propose(
+			RuntimeOrigin::signed(ALICE),
+			1,
+			Box::new(proposal.clone()),
+			length
+		));
+		System::assert_last_event(RuntimeEvent::Council(pallet_collective::Event::Executed {
+			proposal_hash: hash,
+			result: Err(DispatchError::BadOrigin),
+		}));
+		assert_eq!(owner([1; 32]), Some(CHARLIE));
+
+		// Two thirds of the council are.
+		assert_ok!(Council::propose(
+			RuntimeOrigin::signed(ALICE),
+			2,
+			Box::new(proposal.clone()),
+			length
+		));
+		assert_ok!(Council::vote(RuntimeOrigin::signed(ALICE), hash, 0, true));
+		assert_ok!(Council::vote(RuntimeOrigin::signed(BOB), hash, 0, true));
+		assert_ok!(Council::close(
+			RuntimeOrigin::signed(BOB),
+			hash,
+			0,
+			proposal.get_dispatch_info().weight,
+			length
+		));
+		System::assert_has_event(RuntimeEvent::Council(pallet_collective::Event::Executed {
+			proposal_hash: hash,
+			result: Ok(()),
+		}));
+		assert_eq!(owner([1; 32]), Some(BOB));
+	});
+}
+
+#[test]
+fn transfer_refunds_weight_by_owned_kitties() {
+	new_test_ext().execute_with(|| {
+		let transfer =
+			|kitty_id| RuntimeCall::TemplateModule(TemplateCall::transfer { to: BOB, kitty_id });
+		let charged_max = transfer([0; 32]).get_dispatch_info().weight;
+		assert_eq!(
+			charged_max,
+			SubstrateWeight::<Runtime>::transfer(MAX_KITTIES_OWNED, MAX_KITTIES_OWNED - 1)
+		);
+
+		let kitties = mint_kitties(ALICE, 1, 1);
+		let few = charged_weight(transfer(kitties[0]), ALICE);
+		assert_eq!(few, SubstrateWeight::<Runtime>::transfer(1, 0));
+
+		let kitties = mint_kitties(ALICE, 2, 60);
+		mint_kitties(BOB, 3, 39);
+		let many = charged_weight(transfer(kitties[0]), ALICE);
+		assert_eq!(many, SubstrateWeight::<Runtime>::transfer(60, 40));
+
+		assert!(few.ref_time() < many.ref_time());
+		assert!(many.ref_time() < charged_max.ref_time());
+	});
+}
+
+#[test]
+fn buy_kitty_refunds_weight_by_owned_kitties() {
+	new_test_ext().execute_with(|| {
+		let buy_kitty = |kitty_id| {
+			RuntimeCall::TemplateModule(TemplateCall::buy_kitty { kitty_id, max_price: UNIT })
+		};
+		let list = |kitty_id| {
+			assert_ok!(TemplateModule::set_price(
+				RuntimeOrigin::signed(CHARLIE),
+				kitty_id,
+				Some(UNIT)
+			));
+		};
+		let charged_max = buy_kitty([0; 32]).get_dispatch_info().weight;
+		assert_eq!(
+			charged_max,
+			SubstrateWeight::<Runtime>::buy_kitty(MAX_KITTIES_OWNED, MAX_KITTIES_OWNED - 1)
+		);
+
+		let kitties = mint_kitties(CHARLIE, 1, 1);
+		list(kitties[0]);
+		let few = charged_weight(buy_kitty(kitties[0]), BOB);
+		assert_eq!(few, SubstrateWeight::<Runtime>::buy_kitty(1, 0));
+
+		let kitties = mint_kitties(CHARLIE, 2, 60);
+		mint_kitties(BOB, 3, 39);
+		list(kitties[0]);
+		let many = charged_weight(buy_kitty(kitties[0]), BOB);
+		assert_eq!(many, SubstrateWeight::<Runtime>::buy_kitty(60, 40));
+
+		assert!(few.ref_time() < many.ref_time());
+		assert!(many.ref_time() < charged_max.ref_time());
+	});
+}